@@ -4,7 +4,7 @@ use super::{
     TaxType,
 };
 use super::{error::ApiError, extract_context};
-use async_graphql::{Context, FieldResult, Object, Union};
+use async_graphql::{Context, ErrorExtensions, FieldResult, Object, Union};
 use chain_impl_mockchain::certificate;
 
 // interface for grouping certificates as a graphl union
@@ -56,7 +56,7 @@ impl StakeDelegation {
             .to_single_account()
             .ok_or_else(||
                 // TODO: Multisig address?
-                ApiError::Unimplemented.into())
+                ApiError::Unimplemented.extend())
             .map(|single| {
                 chain_addr::Address(discrimination, chain_addr::Kind::Account(single.into()))
             })
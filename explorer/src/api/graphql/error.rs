@@ -1,3 +1,4 @@
+use async_graphql::{ErrorExtensions, FieldError};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -15,3 +16,26 @@ pub enum ApiError {
     #[error("invalid address {0}")]
     InvalidAddress(String),
 }
+
+impl ApiError {
+    /// Stable identifier of the error kind, sent as the `code` extension of
+    /// the GraphQL error so clients don't have to match on messages.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::InternalError(_) => "INTERNAL_ERROR",
+            ApiError::NotFound(_) => "NOT_FOUND",
+            ApiError::Unimplemented => "UNIMPLEMENTED",
+            ApiError::ArgumentError(_) => "INVALID_ARGUMENT",
+            ApiError::InvalidCursor(_) => "INVALID_CURSOR",
+            ApiError::InvalidAddress(_) => "INVALID_ADDRESS",
+        }
+    }
+}
+
+impl ErrorExtensions for ApiError {
+    fn extend(&self) -> FieldError {
+        FieldError::new(self.to_string()).extend_with(|_, extensions| {
+            extensions.set("code", self.code());
+        })
+    }
+}
@@ -4,7 +4,7 @@ mod connections;
 mod error;
 mod scalars;
 
-use self::config_param::{EpochStabilityDepth, LinearFee};
+use self::config_param::{ConsensusType, EpochStabilityDepth, LinearFee};
 use self::scalars::{
     BlockCount, ChainLength, EpochNumber, ExternalProposalId, IndexCursor, NonZero, PayloadType,
     PoolCount, PoolId, PublicKey, Slot, TransactionCount, Value, VoteOptionRange,
@@ -26,10 +26,11 @@ use crate::db::persistent_sequence::PersistentSequence;
 use crate::db::{ExplorerDb, Settings as ChainSettings};
 use async_graphql::connection::{query, Connection, Edge, EmptyFields};
 use async_graphql::{
-    Context, EmptyMutation, FieldError, FieldResult, Object, SimpleObject, Subscription, Union,
+    Context, EmptyMutation, ErrorExtensions, FieldResult, Object, SimpleObject, Subscription, Union,
 };
 use cardano_legacy_address::Addr as OldAddress;
 use certificates::*;
+use chain_crypto::bech32::Bech32;
 use chain_impl_mockchain::key::BftLeaderId;
 use chain_impl_mockchain::{
     block::{BlockDate as InternalBlockDate, Epoch as InternalEpoch, HeaderId as HeaderHash},
@@ -52,7 +53,7 @@ impl Branch {
             .get_branch(&id)
             .await
             .map(|state| Branch { state, id })
-            .ok_or_else(|| ApiError::NotFound("branch not found".to_string()).into())
+            .ok_or_else(|| ApiError::NotFound("branch not found".to_string()).extend())
     }
 
     fn from_id_and_state(id: HeaderHash, state: crate::db::Ref) -> Branch {
@@ -148,7 +149,7 @@ impl Branch {
         let address = chain_addr::AddressReadable::from_string_anyprefix(&address_bech32)
             .map(|adr| ExplorerAddress::New(adr.to_address()))
             .or_else(|_| OldAddress::from_str(&address_bech32).map(ExplorerAddress::Old))
-            .map_err(|_| ApiError::InvalidAddress(address_bech32.to_string()))?;
+            .map_err(|_| ApiError::InvalidAddress(address_bech32.to_string()).extend())?;
 
         let transactions = self
             .state
@@ -473,12 +474,10 @@ pub struct Block {
 impl Block {
     async fn from_string_hash(hash: String, db: &ExplorerDb) -> FieldResult<Block> {
         let hash = HeaderHash::from_str(&hash)?;
-        let block = Block {
-            hash,
-            contents: Default::default(),
-        };
-
-        block.fetch_explorer_block(db).await.map(|_| block)
+        db.get_block(&hash)
+            .await
+            .map(Block::from_contents)
+            .ok_or_else(|| ApiError::NotFound(format!("block not found: {}", hash)).extend())
     }
 
     fn from_valid_hash(hash: HeaderHash) -> Block {
@@ -502,6 +501,7 @@ impl Block {
         } else {
             let block = db.get_block(&self.hash).await.ok_or_else(|| {
                 ApiError::InternalError("Couldn't find block's contents in explorer".to_owned())
+                    .extend()
             })?;
 
             *contents = Some(Arc::clone(&block));
@@ -515,6 +515,7 @@ impl Block {
                 .await
                 .ok_or_else(|| {
                     ApiError::InternalError("Couldn't find block's contents in explorer".to_owned())
+                        .extend()
                 })?;
 
         let mut contents = self.contents.lock().await;
@@ -744,7 +745,7 @@ impl Transaction {
             .await;
 
         if block_hashes.is_empty() {
-            return Err(ApiError::NotFound(format!("transaction not found: {}", &id,)).into());
+            return Err(ApiError::NotFound(format!("transaction not found: {}", &id,)).extend());
         } else {
             Ok(Transaction {
                 id,
@@ -781,9 +782,10 @@ impl Transaction {
         };
 
         if block_ids.is_empty() {
-            return Err(FieldError::from(ApiError::InternalError(
+            return Err(ApiError::InternalError(
                 "Transaction is not present in any block".to_owned(),
-            )));
+            )
+            .extend());
         }
 
         let mut result = Vec::new();
@@ -794,9 +796,10 @@ impl Transaction {
                 .get_block(&block_id)
                 .await
                 .ok_or_else(|| {
-                    FieldError::from(ApiError::InternalError(
+                    ApiError::InternalError(
                         "transaction is in explorer but couldn't find its block".to_owned(),
-                    ))
+                    )
+                    .extend()
                 })?;
 
             result.push(block);
@@ -816,9 +819,10 @@ impl Transaction {
                 .get_block(&self.block_hashes[0])
                 .await
                 .ok_or_else(|| {
-                    FieldError::from(ApiError::InternalError(
+                    ApiError::InternalError(
                         "failed to fetch block containing the transaction".to_owned(),
-                    ))
+                    )
+                    .extend()
                 })?;
 
             Ok(block
@@ -828,6 +832,7 @@ impl Transaction {
                     ApiError::InternalError(
                         "transaction was not found in respective block".to_owned(),
                     )
+                    .extend()
                 })?
                 .clone())
         }
@@ -905,7 +910,7 @@ impl Address {
         let addr = chain_addr::AddressReadable::from_string_anyprefix(bech32)
             .map(|adr| ExplorerAddress::New(adr.to_address()))
             .or_else(|_| OldAddress::from_str(bech32).map(ExplorerAddress::Old))
-            .map_err(|_| ApiError::InvalidAddress(bech32.to_string()))?;
+            .map_err(|_| ApiError::InvalidAddress(bech32.to_string()).extend())?;
 
         Ok(Address { id: addr })
     }
@@ -932,7 +937,7 @@ impl Address {
     }
 
     async fn delegation(&self, _context: &Context<'_>) -> FieldResult<Pool> {
-        Err(ApiError::Unimplemented.into())
+        Err(ApiError::Unimplemented.extend())
     }
 }
 
@@ -999,12 +1004,12 @@ impl Pool {
         let blocks = db
             .get_stake_pool_blocks(&id)
             .await
-            .ok_or_else(|| ApiError::NotFound("Stake pool not found".to_owned()))?;
+            .ok_or_else(|| ApiError::NotFound("Stake pool not found".to_owned()).extend())?;
 
         let data = db
             .get_stake_pool_data(&id)
             .await
-            .ok_or_else(|| ApiError::NotFound("Stake pool not found".to_owned()))?;
+            .ok_or_else(|| ApiError::NotFound("Stake pool not found".to_owned()).extend())?;
 
         Ok(Pool {
             id,
@@ -1052,6 +1057,7 @@ impl Pool {
                 .await
                 .ok_or_else(|| {
                     ApiError::InternalError("Stake pool in block is not indexed".to_owned())
+                        .extend()
                 })?,
         };
 
@@ -1119,7 +1125,7 @@ impl Pool {
                 .get_stake_pool_data(&self.id)
                 .await
                 .map(|data| PoolRegistration::from(data.registration.clone()))
-                .ok_or_else(|| ApiError::NotFound("Stake pool not found".to_owned()).into()),
+                .ok_or_else(|| ApiError::NotFound("Stake pool not found".to_owned()).extend()),
         }
     }
 
@@ -1130,7 +1136,7 @@ impl Pool {
                 .db
                 .get_stake_pool_data(&self.id)
                 .await
-                .ok_or_else(|| ApiError::NotFound("Stake pool not found".to_owned()).into())
+                .ok_or_else(|| ApiError::NotFound("Stake pool not found".to_owned()).extend())
                 .map(|data| {
                     data.retirement
                         .as_ref()
@@ -1156,6 +1162,28 @@ impl Settings {
                 .epoch_stability_depth,
         )
     }
+
+    pub async fn consensus_version(&self, context: &Context<'_>) -> ConsensusType {
+        From::from(
+            &extract_context(context)
+                .db
+                .blockchain_config
+                .consensus_version,
+        )
+    }
+
+    /// Hex-encoded ids of the committee members set in block0, the same
+    /// list as the node REST `committees` endpoint. These are the member
+    /// ids, not their vote plan communication keys
+    pub async fn committee_ids(&self, context: &Context<'_>) -> Vec<String> {
+        extract_context(context)
+            .db
+            .blockchain_config
+            .committees
+            .iter()
+            .map(|id| id.to_hex())
+            .collect()
+    }
 }
 
 #[derive(SimpleObject)]
@@ -1200,7 +1228,7 @@ impl Epoch {
 
     /// Not yet implemented
     pub async fn stake_distribution(&self) -> FieldResult<StakeDistribution> {
-        Err(ApiError::Unimplemented.into())
+        Err(ApiError::Unimplemented.extend())
     }
 
     pub async fn first_block(&self, context: &Context<'_>) -> Option<Block> {
@@ -1220,6 +1248,51 @@ impl Epoch {
             .await
             .map_or(0u32.into(), |data| data.total_blocks.into())
     }
+
+    /// Rewards distributed at the end of the epoch, `null` until the chain
+    /// moves past it. Not yet implemented for epochs ending after the start
+    /// of rewards, as the explorer does not keep the ledger reward pots
+    pub async fn rewards(&self, context: &Context<'_>) -> FieldResult<Option<EpochRewards>> {
+        let db = &extract_context(context).db;
+        let (tip, _) = db.get_tip().await;
+        let tip_epoch = match db.get_block(&tip).await {
+            Some(block) => block.date.epoch,
+            None => return Ok(None),
+        };
+        if self.id >= tip_epoch {
+            return Ok(None);
+        }
+        match db.blockchain_config.rewards_start_epoch {
+            Some(start) if self.id >= start => Err(ApiError::Unimplemented.extend()),
+            _ => Ok(Some(EpochRewards::before_rewards_start())),
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct EpochRewards {
+    /// Set when the epoch ended before rewards began, nothing was distributed
+    before_rewards_start: bool,
+    total: Value,
+    treasury: Value,
+    pools: Vec<PoolReward>,
+}
+
+impl EpochRewards {
+    fn before_rewards_start() -> Self {
+        EpochRewards {
+            before_rewards_start: true,
+            total: Value::from(0u64),
+            treasury: Value::from(0u64),
+            pools: Vec::new(),
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct PoolReward {
+    pool: Pool,
+    reward: Value,
 }
 
 #[derive(SimpleObject)]
@@ -1296,6 +1369,9 @@ pub struct VotePlanStatus {
     vote_end: BlockDate,
     committee_end: BlockDate,
     payload_type: PayloadType,
+    /// Election public key, in bech32, to encrypt votes for a private vote
+    /// plan. Not set for public vote plans.
+    vote_encryption_key: Option<String>,
     proposals: Vec<VoteProposalStatus>,
 }
 
@@ -1305,7 +1381,7 @@ impl VotePlanStatus {
         context: &Context<'_>,
     ) -> FieldResult<Self> {
         let vote_plan_id = chain_impl_mockchain::certificate::VotePlanId::from_str(&vote_plan_id.0)
-            .map_err(|err| -> FieldError { ApiError::InvalidAddress(err.to_string()).into() })?;
+            .map_err(|err| ApiError::InvalidAddress(err.to_string()).extend())?;
         if let Some(vote_plan) = extract_context(context)
             .db
             .get_vote_plan_by_id(&vote_plan_id)
@@ -1314,7 +1390,7 @@ impl VotePlanStatus {
             return Ok(Self::vote_plan_from_data(vote_plan));
         }
 
-        Err(ApiError::NotFound(format!("Vote plan with id {} not found", vote_plan_id)).into())
+        Err(ApiError::NotFound(format!("Vote plan with id {} not found", vote_plan_id)).extend())
     }
 
     pub fn vote_plan_from_data(vote_plan: Arc<ExplorerVotePlan>) -> Self {
@@ -1324,6 +1400,7 @@ impl VotePlanStatus {
             vote_end,
             committee_end,
             payload_type,
+            vote_encryption_key,
             proposals,
         } = (*vote_plan).clone();
 
@@ -1333,6 +1410,7 @@ impl VotePlanStatus {
             vote_end: BlockDate::from(vote_end),
             committee_end: BlockDate::from(committee_end),
             payload_type: PayloadType::from(payload_type),
+            vote_encryption_key: vote_encryption_key.map(|key| key.to_bech32_str()),
             proposals: proposals
                 .into_iter()
                 .map(|proposal| VoteProposalStatus {
@@ -1555,6 +1633,11 @@ impl Query {
     ) -> FieldResult<VotePlanStatus> {
         VotePlanStatus::vote_plan_from_id(VotePlanId(id), context).await
     }
+
+    /// version of the explorer serving the request
+    pub async fn version(&self) -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
 }
 
 pub struct Subscription;
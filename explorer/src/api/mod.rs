@@ -77,7 +77,7 @@ pub fn filter(
     .data(EContext { db, settings })
     .finish();
 
-    let graphql_post = async_graphql_warp::graphql(schema.clone())
+    let graphql_post = async_graphql_warp::graphql_batch(schema.clone())
         .and_then(|(schema, request)| handler(schema, request));
 
     let graphql = warp::path!("graphql").and(graphql_post).boxed();
@@ -126,11 +126,13 @@ pub fn filter(
         }))
 }
 
+/// Accepts both a single operation and an array of operations, as sent by
+/// clients batching several queries in one request.
 pub async fn handler(
     schema: graphql::Schema,
-    request: async_graphql::Request,
+    request: async_graphql::BatchRequest,
 ) -> Result<impl Reply, std::convert::Infallible> {
-    Ok::<_, std::convert::Infallible>(async_graphql_warp::Response::from(
-        schema.execute(request).await,
+    Ok::<_, std::convert::Infallible>(async_graphql_warp::BatchResponse::from(
+        schema.execute_batch(request).await,
     ))
 }
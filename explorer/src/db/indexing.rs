@@ -108,6 +108,8 @@ pub struct ExplorerVotePlan {
     pub vote_end: BlockDate,
     pub committee_end: BlockDate,
     pub payload_type: PayloadType,
+    /// Election key votes are encrypted with, only set for private vote plans
+    pub vote_encryption_key: Option<chain_vote::ElectionPublicKey>,
     pub proposals: Vec<ExplorerVoteProposal>,
 }
 
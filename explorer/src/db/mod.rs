@@ -24,10 +24,13 @@ use chain_impl_mockchain::{
     block::{Block, ChainLength, Epoch},
     certificate::{Certificate, PoolId},
     chaintypes::ConsensusVersion,
-    config::ConfigParam,
+    config::{ConfigParam, RewardParams},
     fragment::{ConfigParams, Fragment, FragmentId},
 };
-use chain_impl_mockchain::{fee::LinearFee, vote::PayloadType};
+use chain_impl_mockchain::{
+    fee::LinearFee,
+    vote::{CommitteeId, PayloadType},
+};
 use futures::prelude::*;
 use multiverse::Multiverse;
 pub use multiverse::Ref;
@@ -74,6 +77,11 @@ pub struct BlockchainConfig {
     pub consensus_version: ConsensusVersion,
     pub fees: LinearFee,
     pub epoch_stability_depth: u32,
+    /// Committee members set in the initial params
+    pub committees: Vec<CommitteeId>,
+    /// First epoch at the end of which rewards are distributed, `None` when
+    /// the initial params set no reward parameters
+    pub rewards_start_epoch: Option<Epoch>,
 }
 
 /// Inmutable data structure used to represent the explorer's state at a given Block
@@ -601,6 +609,14 @@ fn apply_block_to_vote_plans(
                             vote_end: vote_plan.vote_end(),
                             committee_end: vote_plan.committee_end(),
                             payload_type: vote_plan.payload_type(),
+                            vote_encryption_key: match vote_plan.payload_type() {
+                                PayloadType::Public => None,
+                                PayloadType::Private => {
+                                    Some(chain_vote::ElectionPublicKey::from_participants(
+                                        vote_plan.committee_public_keys(),
+                                    ))
+                                }
+                            },
                             proposals: vote_plan
                                 .proposals()
                                 .iter()
@@ -772,6 +788,8 @@ impl BlockchainConfig {
         let mut consensus_version: Option<ConsensusVersion> = None;
         let mut fees: Option<LinearFee> = None;
         let mut epoch_stability_depth: Option<u32> = None;
+        let mut committees: Vec<CommitteeId> = Vec::new();
+        let mut rewards_start_epoch: Option<Epoch> = None;
 
         for p in params.iter() {
             match p {
@@ -787,6 +805,18 @@ impl BlockchainConfig {
                 ConfigParam::EpochStabilityDepth(d) => {
                     epoch_stability_depth.replace(*d);
                 }
+                ConfigParam::AddCommitteeId(id) => {
+                    if !committees.contains(id) {
+                        committees.push(*id);
+                    }
+                }
+                ConfigParam::RemoveCommitteeId(id) => {
+                    committees.retain(|committee| committee != id);
+                }
+                ConfigParam::RewardParams(RewardParams::Linear { epoch_start, .. })
+                | ConfigParam::RewardParams(RewardParams::Halving { epoch_start, .. }) => {
+                    rewards_start_epoch.replace(*epoch_start);
+                }
                 _ => (),
             }
         }
@@ -798,6 +828,8 @@ impl BlockchainConfig {
             fees: fees.expect("fees not found in initial params"),
             epoch_stability_depth: epoch_stability_depth
                 .expect("epoch stability depth not found in initial params"),
+            committees,
+            rewards_start_epoch,
         }
     }
 }
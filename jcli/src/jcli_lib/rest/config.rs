@@ -1,52 +1,111 @@
+use super::unix_socket::{self, UnixSocketResponse, UNIX_SOCKET_BASE_URL};
 use bytes::Bytes;
 use reqwest::{
     blocking::{Client, RequestBuilder},
     Url,
 };
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 use structopt::StructOpt;
 use thiserror::Error;
 
+/// Environment variable `--host` defaults to.
+const HOST_ENV_VAR: &str = "JORMUNGANDR_RESTAPI_URL";
+
 #[derive(StructOpt, Clone)]
 pub struct RestArgs {
     /// node API address. Must always have `http://` or `https://` prefix.
     /// E.g. `-h http://127.0.0.1`, `--host https://node.com:8443/cardano/api`
-    #[structopt(short, long, env = "JORMUNGANDR_RESTAPI_URL")]
-    pub host: Url,
+    #[structopt(
+        short,
+        long,
+        env = "JORMUNGANDR_RESTAPI_URL",
+        required_unless = "SOCKET_PATH"
+    )]
+    pub host: Option<Url>,
+    /// unix domain socket the node API listens on, used instead of `--host`
+    /// if both are given. Requests are sent under the path of the host if
+    /// one is set, e.g. `/cardano/api`, and under `/api` otherwise.
+    #[structopt(long, name = "SOCKET_PATH")]
+    pub unix_socket: Option<PathBuf>,
     /// print additional debug information to stderr.
     /// The output format is intentionally undocumented and unstable
     #[structopt(long)]
     debug: bool,
     /// An optional TLS root certificate to be used in a case when the
     /// certificate CA is not present within the webpki certificate bundle.
-    #[structopt(long, name = "PATH", env = "JORMUNGANDR_TLS_CERT_PATH")]
+    #[structopt(
+        long,
+        name = "PATH",
+        alias = "tls-ca-cert",
+        env = "JORMUNGANDR_TLS_CERT_PATH"
+    )]
     tls_cert_path: Option<PathBuf>,
+    /// PEM-encoded certificate presented to the node (or a proxy in front of
+    /// it) requiring TLS client authentication. Requires `--tls-client-key`.
+    #[structopt(long, name = "CERT_PATH")]
+    tls_client_cert: Option<PathBuf>,
+    /// PEM-encoded private key of the `--tls-client-cert` certificate.
+    #[structopt(long, name = "KEY_PATH")]
+    tls_client_key: Option<PathBuf>,
+    /// how long to wait for the node to answer a request, in seconds
+    #[structopt(long, name = "SECONDS", default_value = "30")]
+    timeout: u64,
+    /// do not contact the node, print the method, URL and body of the
+    /// request instead, using the output format of the command
+    #[structopt(long)]
+    dry_run: bool,
 }
 
 pub struct RestClient {
     client: Client,
+    unix_socket: Option<PathBuf>,
     debug: bool,
     base_url: Url,
+    timeout: Duration,
+    dry_run: bool,
 }
 
 pub struct RestRequestBuilder {
     client: Client,
+    unix_socket: Option<PathBuf>,
     request_builder: RequestBuilder,
     debug: bool,
+    timeout: Duration,
+    dry_run: bool,
 }
 
-pub struct RestResponse(reqwest::blocking::Response);
+pub enum RestResponse {
+    Http(reqwest::blocking::Response),
+    /// Response read from `--unix-socket`.
+    UnixSocket(Bytes),
+    /// Description of a request which was not sent because of `--dry-run`.
+    DryRun(serde_json::Value),
+}
 
 #[derive(Debug, Error)]
 pub enum Error {
+    #[error("no host or unix socket given for the node API")]
+    NoHost,
     #[error("Host address '{addr}' isn't valid address base")]
     HostAddrNotBase { addr: Url },
     #[error("could not read the provided certificate")]
     CertIo(#[source] std::io::Error),
+    #[error("invalid TLS client identity: {0}")]
+    TlsConfig(String),
     #[error("expected a valid PEM-encoded certificate")]
     Pem(#[source] reqwest::Error),
-    #[error("failed to build an HTTP client")]
-    Client(#[source] reqwest::Error),
+    #[error("failed to build an HTTP client for '{host}'")]
+    Client {
+        host: Url,
+        #[source]
+        source: reqwest::Error,
+    },
+    #[error("could not connect to the node at '{url}'")]
+    Connect {
+        url: Url,
+        #[source]
+        source: reqwest::Error,
+    },
     #[error("invalid request")]
     Request(#[source] reqwest::Error),
     #[error("could not deserialize the response as JSON")]
@@ -55,8 +114,13 @@ pub enum Error {
     Bytes(#[source] reqwest::Error),
     #[error("could not get the response text")]
     Text(#[source] reqwest::Error),
-    #[error("connection with the node timed out")]
-    Timeout,
+    #[error("request to the node timed out after {0:?}")]
+    RequestTimeout(Duration),
+    #[error("node rejected the request ({status}): {reason}")]
+    Rejected {
+        status: reqwest::StatusCode,
+        reason: String,
+    },
     #[error("node rejected request because of invalid parameters")]
     InvalidParams(#[source] reqwest::Error),
     #[error("node internal error")]
@@ -65,24 +129,103 @@ pub enum Error {
     Redirecton(#[source] reqwest::Error),
     #[error("communication with node failed in unexpected way")]
     Unexpected(#[source] reqwest::Error),
+    #[error("unix socket '{path}' does not exist")]
+    UnixSocketNotFound { path: PathBuf },
+    #[error("communication over unix socket '{path}' failed")]
+    UnixSocketIo {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("invalid HTTP response on the unix socket: {0}")]
+    UnixSocketResponseInvalid(&'static str),
+    #[error("response on the unix socket is larger than {limit} bytes")]
+    UnixSocketResponseTooLarge { limit: u64 },
+    #[error("streamed request bodies cannot be sent on a unix socket")]
+    UnixSocketStreamedBody,
+    #[error("unix sockets are not supported on this platform")]
+    UnixSocketUnsupported,
+    #[error("node answered with unexpected status {0}")]
+    UnixSocketStatus(reqwest::StatusCode),
+    #[error("could not deserialize the response as JSON")]
+    UnixSocketJson(#[source] serde_json::Error),
+    #[error("the request was not sent because of --dry-run, there is no response to read")]
+    DryRun,
+}
+
+impl Error {
+    /// Whether the node answered the request with `404 Not Found`.
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            Error::InvalidParams(e) => e.status() == Some(reqwest::StatusCode::NOT_FOUND),
+            Error::Rejected { status, .. } => *status == reqwest::StatusCode::NOT_FOUND,
+            _ => false,
+        }
+    }
 }
 
 impl RestArgs {
+    /// Arguments for the node REST API at `host`, with the defaults of the
+    /// command line.
+    pub fn new(host: Url) -> Self {
+        Self {
+            host: Some(host),
+            unix_socket: None,
+            debug: false,
+            tls_cert_path: None,
+            tls_client_cert: None,
+            tls_client_key: None,
+            timeout: 30,
+            dry_run: false,
+        }
+    }
+
     pub fn client(self) -> Result<RestClient, Error> {
-        use reqwest::{blocking::ClientBuilder, Certificate};
+        use reqwest::{blocking::ClientBuilder, Certificate, Identity};
         use std::{fs::File, io::Read};
 
         let Self {
             tls_cert_path,
+            tls_client_cert,
+            tls_client_key,
             host,
+            unix_socket,
             debug,
+            timeout,
+            dry_run,
         } = self;
+        let timeout = Duration::from_secs(timeout);
+
+        let host = match (host, &unix_socket) {
+            (host, Some(path)) => {
+                // nothing is sent with --dry-run, the socket may not exist yet
+                if !dry_run && !path.exists() {
+                    return Err(Error::UnixSocketNotFound { path: path.clone() });
+                }
+                let mut base_url = Url::parse(UNIX_SOCKET_BASE_URL).unwrap();
+                if let Some(host) = host {
+                    // a host set in the environment is used silently
+                    if !is_host_from_env(&host) {
+                        eprintln!(
+                            "warning: both --host '{}' and --unix-socket '{}' are set, using the socket under '{}'",
+                            host,
+                            path.display(),
+                            host.path()
+                        );
+                    }
+                    base_url.set_path(host.path());
+                }
+                base_url
+            }
+            (Some(host), None) => host,
+            (None, None) => return Err(Error::NoHost),
+        };
 
         if host.cannot_be_a_base() {
             return Err(Error::HostAddrNotBase { addr: host });
         }
 
-        let client_builder = ClientBuilder::new();
+        let client_builder = ClientBuilder::new().timeout(timeout);
 
         // load certificate
         let client_builder = if let Some(path) = tls_cert_path {
@@ -97,12 +240,42 @@ impl RestArgs {
             client_builder
         };
 
-        let client = client_builder.build().map_err(Error::Client)?;
+        // load client identity, rustls wants the certificate and its key in
+        // a single PEM buffer
+        let client_builder = match (tls_client_cert, tls_client_key) {
+            (Some(cert_path), Some(key_path)) => {
+                let mut buf = Vec::new();
+                for path in &[cert_path, key_path] {
+                    File::open(path)
+                        .map_err(Error::CertIo)?
+                        .read_to_end(&mut buf)
+                        .map_err(Error::CertIo)?;
+                    buf.push(b'\n');
+                }
+                let identity =
+                    Identity::from_pem(&buf).map_err(|e| Error::TlsConfig(e.to_string()))?;
+                client_builder.use_rustls_tls().identity(identity)
+            }
+            (None, None) => client_builder,
+            _ => {
+                return Err(Error::TlsConfig(
+                    "--tls-client-cert and --tls-client-key must be given together".to_string(),
+                ))
+            }
+        };
+
+        let client = client_builder.build().map_err(|source| Error::Client {
+            host: host.clone(),
+            source,
+        })?;
 
         let rest_client = RestClient {
             client,
+            unix_socket,
             debug,
             base_url: host,
+            timeout,
+            dry_run,
         };
 
         Ok(rest_client)
@@ -128,19 +301,35 @@ impl RestClient {
     {
         let Self {
             client,
+            unix_socket,
             base_url,
             debug,
+            timeout,
+            dry_run,
         } = self;
         let url = make_url(base_url, address_segments);
         let request_builder = f(&client, url);
         RestRequestBuilder {
             client,
+            unix_socket,
             request_builder,
             debug,
+            timeout,
+            dry_run,
         }
     }
 }
 
+/// Whether `host` is the one set in the environment rather than given with
+/// `--host`.
+fn is_host_from_env(host: &Url) -> bool {
+    std::env::var(HOST_ENV_VAR)
+        .ok()
+        .and_then(|url| Url::parse(&url).ok())
+        .as_ref()
+        == Some(host)
+}
+
 fn make_url(mut host: Url, segments: &[&str]) -> Url {
     host.path_segments_mut()
         .map(|mut host_segments| {
@@ -183,8 +372,11 @@ impl RestRequestBuilder {
     pub fn execute(self) -> Result<RestResponse, Error> {
         let Self {
             client,
+            unix_socket,
             request_builder,
             debug,
+            timeout,
+            dry_run,
         } = self;
 
         let request = request_builder.build().map_err(Error::Request)?;
@@ -192,13 +384,22 @@ impl RestRequestBuilder {
         if debug {
             eprintln!("Request: {:?}", request);
         }
+        if dry_run {
+            return Ok(RestResponse::DryRun(describe_request(&request)));
+        }
+        if let Some(path) = unix_socket {
+            return execute_unix_socket(&path, &request, timeout, debug);
+        }
 
+        let url = request.url().clone();
         let response = client
             .execute(request)
             .and_then(|response| response.error_for_status())
             .map_err(|e| {
                 if e.is_timeout() {
-                    Error::Timeout
+                    Error::RequestTimeout(timeout)
+                } else if e.is_connect() {
+                    Error::Connect { url, source: e }
                 } else if let Some(status) = e.status() {
                     if status.is_client_error() {
                         Error::InvalidParams(e)
@@ -218,8 +419,53 @@ impl RestRequestBuilder {
             eprintln!("Response: {:?}", response);
         }
 
-        Ok(RestResponse(response))
+        Ok(RestResponse::Http(response))
+    }
+}
+
+/// Client errors are reported as `Rejected`, with the body of the answer as
+/// the reason.
+fn execute_unix_socket(
+    path: &std::path::Path,
+    request: &reqwest::blocking::Request,
+    timeout: Duration,
+    debug: bool,
+) -> Result<RestResponse, Error> {
+    let UnixSocketResponse { status, body } = unix_socket::send(path, request, timeout)?;
+    if debug {
+        eprintln!("Response: {} ({} bytes)", status, body.len());
+    }
+    if status.is_client_error() {
+        return Err(Error::Rejected {
+            status,
+            reason: String::from_utf8_lossy(&body).into_owned(),
+        });
+    }
+    if !status.is_success() {
+        return Err(Error::UnixSocketStatus(status));
     }
+    Ok(RestResponse::UnixSocket(body))
+}
+
+/// Method, URL and body of `request`. JSON bodies are kept as is, others
+/// are shown as text, or hex when they are not valid UTF-8.
+fn describe_request(request: &reqwest::blocking::Request) -> serde_json::Value {
+    let url = request.url();
+    let body = request.body().and_then(|body| body.as_bytes()).map(|body| {
+        serde_json::from_slice(body).unwrap_or_else(|_| match std::str::from_utf8(body) {
+            Ok(text) => serde_json::Value::from(text),
+            Err(_) => serde_json::Value::from(hex::encode(body)),
+        })
+    });
+    serde_json::json!({
+        "method": request.method().as_str(),
+        "url": url.as_str(),
+        "path": url.path(),
+        "segments": url
+            .path_segments()
+            .map(|segments| segments.collect::<Vec<_>>()),
+        "body": body,
+    })
 }
 
 impl RestResponse {
@@ -227,14 +473,28 @@ impl RestResponse {
     where
         T: serde::de::DeserializeOwned,
     {
-        self.0.json().map_err(Error::Json)
+        match self {
+            RestResponse::Http(response) => response.json().map_err(Error::Json),
+            RestResponse::UnixSocket(body) => {
+                serde_json::from_slice(&body).map_err(Error::UnixSocketJson)
+            }
+            RestResponse::DryRun(_) => Err(Error::DryRun),
+        }
     }
 
     pub fn bytes(self) -> Result<Bytes, Error> {
-        self.0.bytes().map_err(Error::Bytes)
+        match self {
+            RestResponse::Http(response) => response.bytes().map_err(Error::Bytes),
+            RestResponse::UnixSocket(body) => Ok(body),
+            RestResponse::DryRun(_) => Err(Error::DryRun),
+        }
     }
 
     pub fn text(self) -> Result<String, Error> {
-        self.0.text().map_err(Error::Text)
+        match self {
+            RestResponse::Http(response) => response.text().map_err(Error::Text),
+            RestResponse::UnixSocket(body) => Ok(String::from_utf8_lossy(&body).into_owned()),
+            RestResponse::DryRun(_) => Err(Error::DryRun),
+        }
     }
 }
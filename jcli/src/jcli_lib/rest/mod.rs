@@ -1,10 +1,13 @@
 mod config;
+mod unix_socket;
 pub mod v0;
 pub mod v1;
 
-use crate::jcli_lib::utils::{io::ReadYamlError, output_format};
+use crate::jcli_lib::utils::{io::ReadYamlError, output_format, OutputFormat};
 pub use config::RestArgs;
+use config::RestResponse;
 use hex::FromHexError;
+use std::io::Write;
 use structopt::StructOpt;
 use thiserror::Error;
 
@@ -34,6 +37,8 @@ pub enum Error {
     RequestError(#[from] config::Error),
     #[error("error loading data from response")]
     SerdeError(#[from] serde_json::Error),
+    #[error("vote plan '{0}' does not exist or is not active")]
+    VotePlanNotFound(String),
 }
 
 impl From<ReadYamlError> for Error {
@@ -53,3 +58,28 @@ impl Rest {
         }
     }
 }
+
+/// Prints the description of a request which was not sent because of
+/// `--dry-run`, in which case there is no response to read and `None` is
+/// returned. Commands pass the response through it before reading it.
+fn unless_dry_run(
+    response: RestResponse,
+    output_format: &OutputFormat,
+) -> Result<Option<RestResponse>, Error> {
+    unless_dry_run_to(std::io::stdout().lock(), response, output_format)
+}
+
+/// Same as [`unless_dry_run`], writing the description to `writer`.
+fn unless_dry_run_to<W: Write>(
+    writer: W,
+    response: RestResponse,
+    output_format: &OutputFormat,
+) -> Result<Option<RestResponse>, Error> {
+    match response {
+        RestResponse::DryRun(request) => {
+            output_format.write_value(writer, request)?;
+            Ok(None)
+        }
+        response => Ok(Some(response)),
+    }
+}
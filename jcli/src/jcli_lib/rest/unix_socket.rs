@@ -0,0 +1,184 @@
+//! Sending requests to a node REST API listening on a unix domain socket.
+//!
+//! reqwest can only connect over TCP, so requests built with it are written
+//! by hand as HTTP/1.1 on the socket, one connection per request.
+
+use super::config::Error;
+use bytes::Bytes;
+use reqwest::{blocking::Request, StatusCode};
+use std::{path::Path, time::Duration};
+
+/// Base URL of the requests sent on a socket. Only its path matters, the
+/// host is what gets sent in the `Host` header. The path is replaced by the
+/// one of `--host` when it is given.
+pub const UNIX_SOCKET_BASE_URL: &str = "http://localhost/api";
+
+/// Largest response read from a socket, headers included, so that a peer
+/// which never stops sending cannot exhaust the memory of jcli.
+pub const MAX_RESPONSE_SIZE: u64 = 64 * 1024 * 1024;
+
+pub struct UnixSocketResponse {
+    pub status: StatusCode,
+    pub body: Bytes,
+}
+
+#[cfg(unix)]
+pub fn send(
+    path: &Path,
+    request: &Request,
+    timeout: Duration,
+) -> Result<UnixSocketResponse, Error> {
+    use std::{
+        io::{ErrorKind, Read, Write},
+        os::unix::net::UnixStream,
+    };
+
+    let io_error = |source: std::io::Error| match source.kind() {
+        ErrorKind::WouldBlock | ErrorKind::TimedOut => Error::RequestTimeout(timeout),
+        _ => Error::UnixSocketIo {
+            path: path.to_path_buf(),
+            source,
+        },
+    };
+    let body = match request.body() {
+        Some(body) => body.as_bytes().ok_or(Error::UnixSocketStreamedBody)?,
+        None => &[],
+    };
+
+    let url = request.url();
+    let target = match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    };
+    let mut message = format!(
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n",
+        request.method(),
+        target,
+        body.len()
+    )
+    .into_bytes();
+    for (name, value) in request.headers() {
+        message.extend_from_slice(name.as_str().as_bytes());
+        message.extend_from_slice(b": ");
+        message.extend_from_slice(value.as_bytes());
+        message.extend_from_slice(b"\r\n");
+    }
+    message.extend_from_slice(b"\r\n");
+    message.extend_from_slice(body);
+
+    let mut stream = UnixStream::connect(path).map_err(io_error)?;
+    stream.set_read_timeout(Some(timeout)).map_err(io_error)?;
+    stream.set_write_timeout(Some(timeout)).map_err(io_error)?;
+    stream.write_all(&message).map_err(io_error)?;
+    let mut response = Vec::new();
+    stream
+        .take(MAX_RESPONSE_SIZE + 1)
+        .read_to_end(&mut response)
+        .map_err(io_error)?;
+    if response.len() as u64 > MAX_RESPONSE_SIZE {
+        return Err(Error::UnixSocketResponseTooLarge {
+            limit: MAX_RESPONSE_SIZE,
+        });
+    }
+    parse_response(&response)
+}
+
+#[cfg(not(unix))]
+pub fn send(
+    _path: &Path,
+    _request: &Request,
+    _timeout: Duration,
+) -> Result<UnixSocketResponse, Error> {
+    Err(Error::UnixSocketUnsupported)
+}
+
+fn parse_response(response: &[u8]) -> Result<UnixSocketResponse, Error> {
+    let invalid = Error::UnixSocketResponseInvalid;
+    let head_len = find(response, b"\r\n\r\n").ok_or_else(|| invalid("no end of headers"))?;
+    let head = std::str::from_utf8(&response[..head_len]).map_err(|_| invalid("headers"))?;
+    let body = &response[head_len + 4..];
+
+    let mut lines = head.split("\r\n");
+    let status = lines
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse().ok())
+        .and_then(|code| StatusCode::from_u16(code).ok())
+        .ok_or_else(|| invalid("status line"))?;
+    let mut chunked = false;
+    let mut content_length = None;
+    for line in lines {
+        let (name, value) = match line.split_once(':') {
+            Some((name, value)) => (name.trim(), value.trim()),
+            None => return Err(invalid("header line")),
+        };
+        if name.eq_ignore_ascii_case("transfer-encoding") {
+            chunked = value.eq_ignore_ascii_case("chunked");
+        } else if name.eq_ignore_ascii_case("content-length") {
+            content_length = Some(
+                value
+                    .parse::<usize>()
+                    .map_err(|_| invalid("content length"))?,
+            );
+        }
+    }
+
+    let body = if chunked {
+        decode_chunked(body).ok_or_else(|| invalid("chunked body"))?
+    } else {
+        match content_length {
+            Some(len) => body
+                .get(..len)
+                .ok_or_else(|| invalid("truncated body"))?
+                .to_vec(),
+            None => body.to_vec(),
+        }
+    };
+    Ok(UnixSocketResponse {
+        status,
+        body: Bytes::from(body),
+    })
+}
+
+fn decode_chunked(mut body: &[u8]) -> Option<Vec<u8>> {
+    let mut decoded = Vec::new();
+    loop {
+        let line_len = find(body, b"\r\n")?;
+        let size = std::str::from_utf8(&body[..line_len]).ok()?;
+        let size = size.split(';').next()?.trim();
+        let size = usize::from_str_radix(size, 16).ok()?;
+        body = &body[line_len + 2..];
+        if size == 0 {
+            return Some(decoded);
+        }
+        decoded.extend_from_slice(body.get(..size)?);
+        body = body.get(size..)?.strip_prefix(b"\r\n")?;
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_chunked_and_sized_responses() {
+        let chunked = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n\
+            4\r\n{\"a\"\r\n3;ext\r\n:1}\r\n0\r\n\r\n";
+        let response = parse_response(chunked).unwrap();
+        assert_eq!(response.status, StatusCode::OK);
+        assert_eq!(&response.body[..], b"{\"a\":1}");
+
+        let sized = b"HTTP/1.1 404 Not Found\r\ncontent-length: 4\r\n\r\nnope";
+        let response = parse_response(sized).unwrap();
+        assert_eq!(response.status, StatusCode::NOT_FOUND);
+        assert_eq!(&response.body[..], b"nope");
+
+        assert!(parse_response(b"HTTP/1.1 200 OK\r\n").is_err());
+    }
+}
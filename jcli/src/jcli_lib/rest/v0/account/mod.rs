@@ -1,6 +1,7 @@
-use crate::jcli_lib::rest::{Error, RestArgs};
+use crate::jcli_lib::rest::{unless_dry_run, Error, RestArgs, RestResponse};
 use crate::jcli_lib::utils::{AccountId, OutputFormat};
 use jormungandr_lib::interfaces::AccountState;
+use std::io;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -25,9 +26,12 @@ impl Account {
             output_format,
             account_id,
         } = self;
-        let state = request_account_information(args, account_id)?;
-        let formatted = output_format.format_json(serde_json::to_value(state)?)?;
-        println!("{}", formatted);
+        let state: AccountState =
+            match unless_dry_run(get_account(args, account_id)?, &output_format)? {
+                Some(response) => response.json()?,
+                None => return Ok(()),
+            };
+        output_format.write_value(io::stdout().lock(), serde_json::to_value(state)?)?;
         Ok(())
     }
 }
@@ -36,9 +40,12 @@ pub fn request_account_information(
     args: RestArgs,
     account_id: AccountId,
 ) -> Result<AccountState, Error> {
-    args.client()?
+    get_account(args, account_id)?.json().map_err(Into::into)
+}
+
+fn get_account(args: RestArgs, account_id: AccountId) -> Result<RestResponse, Error> {
+    Ok(args
+        .client()?
         .get(&["v0", "account", &account_id.to_url_arg()])
-        .execute()?
-        .json()
-        .map_err(Into::into)
+        .execute()?)
 }
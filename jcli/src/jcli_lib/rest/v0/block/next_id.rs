@@ -1,4 +1,5 @@
-use crate::jcli_lib::rest::{Error, RestArgs};
+use crate::jcli_lib::rest::{unless_dry_run, Error, RestArgs};
+use crate::jcli_lib::utils::OutputFormat;
 use chain_crypto::Blake2b256;
 use structopt::StructOpt;
 
@@ -28,8 +29,11 @@ fn exec_get(args: RestArgs, block_id: String, count: Option<usize>) -> Result<()
         .client()?
         .get(&["v0", "block", &block_id, "next_id"])
         .query(&[("count", count)])
-        .execute()?
-        .bytes()?;
+        .execute()?;
+    let response = match unless_dry_run(response, &OutputFormat::default())? {
+        Some(response) => response.bytes()?,
+        None => return Ok(()),
+    };
     for block_id in response.chunks(Blake2b256::HASH_SIZE) {
         println!("{}", hex::encode(block_id));
     }
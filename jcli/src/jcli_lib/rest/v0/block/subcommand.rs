@@ -1,5 +1,6 @@
 use super::next_id::NextId;
-use crate::jcli_lib::rest::{Error, RestArgs};
+use crate::jcli_lib::rest::{unless_dry_run, Error, RestArgs};
+use crate::jcli_lib::utils::OutputFormat;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -24,11 +25,11 @@ impl Subcommand {
 }
 
 fn exec_get(block_id: String, args: RestArgs) -> Result<(), Error> {
-    let response = args
-        .client()?
-        .get(&["v0", "block", &block_id])
-        .execute()?
-        .bytes()?;
+    let response = args.client()?.get(&["v0", "block", &block_id]).execute()?;
+    let response = match unless_dry_run(response, &OutputFormat::default())? {
+        Some(response) => response.bytes()?,
+        None => return Ok(()),
+    };
     println!("{}", hex::encode(&response));
     Ok(())
 }
@@ -1,5 +1,5 @@
 use crate::jcli_lib::{
-    rest::{Error, RestArgs},
+    rest::{unless_dry_run, Error, RestArgs, RestResponse},
     utils::{io, OutputFormat},
 };
 use chain_core::property::{Deserialize, Serialize};
@@ -44,13 +44,12 @@ impl Message {
 }
 
 fn get_logs(args: RestArgs, output_format: OutputFormat) -> Result<(), Error> {
-    let response = args
-        .client()?
-        .get(&["v0", "fragment", "logs"])
-        .execute()?
-        .json()?;
-    let formatted = output_format.format_json(response)?;
-    println!("{}", formatted);
+    let response = args.client()?.get(&["v0", "fragment", "logs"]).execute()?;
+    let response = match unless_dry_run(response, &output_format)? {
+        Some(response) => response.json()?,
+        None => return Ok(()),
+    };
+    output_format.write_value(std::io::stdout().lock(), response)?;
     Ok(())
 }
 
@@ -59,17 +58,22 @@ fn post_message(args: RestArgs, file: Option<PathBuf>) -> Result<(), Error> {
     let msg_bin = hex::decode(&msg_hex)?;
     let fragment =
         Fragment::deserialize(msg_bin.as_slice()).map_err(Error::InputFragmentMalformed)?;
-    let fragment_id = post_fragment(args, fragment)?;
-    println!("{}", fragment_id);
+    if let Some(response) =
+        unless_dry_run(send_fragment(args, fragment)?, &OutputFormat::default())?
+    {
+        println!("{}", response.text()?);
+    }
     Ok(())
 }
 
 pub fn post_fragment(args: RestArgs, fragment: Fragment) -> Result<String, Error> {
-    let fragment_id = args
+    Ok(send_fragment(args, fragment)?.text()?)
+}
+
+fn send_fragment(args: RestArgs, fragment: Fragment) -> Result<RestResponse, Error> {
+    Ok(args
         .client()?
         .post(&["v0", "message"])
         .body(fragment.serialize_as_vec()?)
-        .execute()?
-        .text()?;
-    Ok(fragment_id)
+        .execute()?)
 }
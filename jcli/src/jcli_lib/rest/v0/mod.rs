@@ -13,7 +13,7 @@ mod stake_pool;
 mod stake_pools;
 mod tip;
 mod utxo;
-mod vote;
+pub mod vote;
 
 use crate::jcli_lib::rest::Error;
 use structopt::StructOpt;
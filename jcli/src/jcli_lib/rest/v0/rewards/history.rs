@@ -1,4 +1,5 @@
-use crate::jcli_lib::rest::{Error, RestArgs};
+use crate::jcli_lib::rest::{unless_dry_run, Error, RestArgs};
+use crate::jcli_lib::utils::OutputFormat;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -19,8 +20,11 @@ impl History {
         let response = args
             .client()?
             .get(&["v0", "rewards", "history", &length.to_string()])
-            .execute()?
-            .text()?;
+            .execute()?;
+        let response = match unless_dry_run(response, &OutputFormat::default())? {
+            Some(response) => response.text()?,
+            None => return Ok(()),
+        };
         println!("{}", response);
         Ok(())
     }
@@ -1,7 +1,8 @@
-use crate::jcli_lib::rest::{Error, RestArgs};
+use crate::jcli_lib::rest::{unless_dry_run, Error, RestArgs, RestResponse};
 use crate::jcli_lib::utils::OutputFormat;
 use jormungandr_lib::interfaces::SettingsDto;
 
+use std::io;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -22,14 +23,23 @@ impl Settings {
             args,
             output_format,
         } = self;
-        let settings = request_settings(args)?;
-        let formatted = output_format.format_json(serde_json::to_value(&settings)?)?;
-        println!("{}", formatted);
+        let settings = match unless_dry_run(get_settings(args)?, &output_format)? {
+            Some(response) => parse_settings(response)?,
+            None => return Ok(()),
+        };
+        output_format.write_value(io::stdout().lock(), serde_json::to_value(&settings)?)?;
         Ok(())
     }
 }
 
 pub fn request_settings(args: RestArgs) -> Result<SettingsDto, Error> {
-    serde_json::from_str(&(args.client()?.get(&["v0", "settings"]).execute()?.text()?))
-        .map_err(Error::SerdeError)
+    parse_settings(get_settings(args)?)
+}
+
+fn get_settings(args: RestArgs) -> Result<RestResponse, Error> {
+    Ok(args.client()?.get(&["v0", "settings"]).execute()?)
+}
+
+fn parse_settings(response: RestResponse) -> Result<SettingsDto, Error> {
+    serde_json::from_str(&response.text()?).map_err(Error::SerdeError)
 }
@@ -1,4 +1,5 @@
-use crate::jcli_lib::rest::{Error, RestArgs};
+use crate::jcli_lib::rest::{unless_dry_run, Error, RestArgs};
+use crate::jcli_lib::utils::OutputFormat;
 use structopt::StructOpt;
 
 /// Shutdown node
@@ -14,8 +15,10 @@ pub enum Shutdown {
 impl Shutdown {
     pub fn exec(self) -> Result<(), Error> {
         let Shutdown::Post { args } = self;
-        args.client()?.get(&["v0", "shutdown"]).execute()?;
-        println!("Success");
+        let response = args.client()?.get(&["v0", "shutdown"]).execute()?;
+        if unless_dry_run(response, &OutputFormat::default())?.is_some() {
+            println!("Success");
+        }
         Ok(())
     }
 }
@@ -1,5 +1,6 @@
-use crate::jcli_lib::rest::{Error, RestArgs};
+use crate::jcli_lib::rest::{unless_dry_run, Error, RestArgs};
 use crate::jcli_lib::utils::OutputFormat;
+use std::io;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -28,9 +29,12 @@ impl Stake {
         if let Some(epoch) = &epoch {
             url.push(epoch);
         }
-        let response = args.client()?.get(&url).execute()?.json()?;
-        let formatted = output_format.format_json(response)?;
-        println!("{}", formatted);
+        let response = args.client()?.get(&url).execute()?;
+        let response = match unless_dry_run(response, &output_format)? {
+            Some(response) => response.json()?,
+            None => return Ok(()),
+        };
+        output_format.write_value(io::stdout().lock(), response)?;
         Ok(())
     }
 }
@@ -1,5 +1,6 @@
-use crate::jcli_lib::rest::{Error, RestArgs};
+use crate::jcli_lib::rest::{unless_dry_run, Error, RestArgs};
 use crate::jcli_lib::utils::OutputFormat;
+use std::io;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -20,13 +21,12 @@ impl StakePools {
             args,
             output_format,
         } = self;
-        let response = args
-            .client()?
-            .get(&["v0", "stake_pools"])
-            .execute()?
-            .json()?;
-        let formatted = output_format.format_json(response)?;
-        println!("{}", formatted);
+        let response = args.client()?.get(&["v0", "stake_pools"]).execute()?;
+        let response = match unless_dry_run(response, &output_format)? {
+            Some(response) => response.json()?,
+            None => return Ok(()),
+        };
+        output_format.write_value(io::stdout().lock(), response)?;
         Ok(())
     }
 }
@@ -1,4 +1,5 @@
-use crate::jcli_lib::rest::{Error, RestArgs};
+use crate::jcli_lib::rest::{unless_dry_run, Error, RestArgs};
+use crate::jcli_lib::utils::OutputFormat;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -16,7 +17,11 @@ impl Tip {
         let args = match self {
             Tip::Get { args } => args,
         };
-        let response = args.client()?.get(&["v0", "tip"]).execute()?.text()?;
+        let response = args.client()?.get(&["v0", "tip"]).execute()?;
+        let response = match unless_dry_run(response, &OutputFormat::default())? {
+            Some(response) => response.text()?,
+            None => return Ok(()),
+        };
         println!("{}", response);
         Ok(())
     }
@@ -1,5 +1,7 @@
-use crate::jcli_lib::rest::{Error, RestArgs};
+use crate::jcli_lib::rest::{unless_dry_run_to, Error, RestArgs, RestResponse};
 use crate::jcli_lib::utils::OutputFormat;
+use serde_json::Value;
+use std::io::{self, Write};
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -15,18 +17,140 @@ pub enum Committees {
 }
 
 impl Committees {
-    pub fn exec(self) -> Result<(), Error> {
-        let Committees::Get {
+    /// Lists the committee members.
+    pub fn get(args: RestArgs, output_format: OutputFormat) -> Self {
+        Committees::Get {
             args,
             output_format,
-        } = self;
-        let response = args
-            .client()?
-            .get(&["v0", "vote", "active", "committees"])
-            .execute()?
-            .json()?;
-        let formatted = output_format.format_json(response)?;
-        println!("{}", formatted);
+        }
+    }
+
+    pub fn exec(self) -> Result<(), Error> {
+        self.write_to(io::stdout().lock())
+    }
+
+    /// Sends the request and writes the answer of the node to `writer`, in
+    /// the selected output format.
+    pub fn write_to<W: Write>(self, mut writer: W) -> Result<(), Error> {
+        let (response, output_format) = self.send()?;
+        if let Some(response) = unless_dry_run_to(&mut writer, response, &output_format)? {
+            output_format.write_value(writer, response.json()?)?;
+        }
         Ok(())
     }
+
+    /// Sends the request and returns the answer of the node instead of
+    /// printing it.
+    pub fn response(self) -> Result<Value, Error> {
+        Ok(self.send()?.0.json()?)
+    }
+
+    fn send(self) -> Result<(RestResponse, OutputFormat), Error> {
+        match self {
+            Committees::Get {
+                args,
+                output_format,
+            } => {
+                let response = args
+                    .client()?
+                    .get(&["v0", "vote", "active", "committees"])
+                    .execute()?;
+                Ok((response, output_format))
+            }
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use assert_fs::TempDir;
+    use std::{io::Read, os::unix::net::UnixListener, thread};
+
+    #[test]
+    fn members_are_written_as_json_lines() {
+        let dir = TempDir::new().unwrap();
+        let socket = dir.path().join("node.sock");
+        let listener = UnixListener::bind(&socket).unwrap();
+        let node = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 256];
+            while !request.ends_with(b"\r\n\r\n") {
+                let read = stream.read(&mut buf).unwrap();
+                assert_ne!(read, 0, "connection closed before the end of the request");
+                request.extend_from_slice(&buf[..read]);
+            }
+            let body = r#"["member_a","member_b"]"#;
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let command = Committees::from_iter_safe(&[
+            "committees",
+            "get",
+            "--unix-socket",
+            socket.to_str().unwrap(),
+            "--output-format",
+            "json-lines",
+        ])
+        .unwrap();
+        let mut output = Vec::new();
+        command.write_to(&mut output).unwrap();
+
+        let request = node.join().unwrap();
+        assert!(request.starts_with("GET /api/v0/vote/active/committees HTTP/1.1"));
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "\"member_a\"\n\"member_b\"\n"
+        );
+    }
+
+    #[test]
+    fn dry_run_writes_the_request_instead_of_sending_it() {
+        let command = Committees::from_iter_safe(&[
+            "committees",
+            "get",
+            "--host",
+            "http://127.0.0.1:1/api",
+            "--dry-run",
+            "--output-format",
+            "json-compact",
+        ])
+        .unwrap();
+        let mut output = Vec::new();
+        command.write_to(&mut output).unwrap();
+
+        let request: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(request["method"], "GET");
+        assert_eq!(request["path"], "/api/v0/vote/active/committees");
+    }
+
+    #[test]
+    fn unix_socket_requests_keep_the_path_of_the_host() {
+        let dir = TempDir::new().unwrap();
+        let command = Committees::from_iter_safe(&[
+            "committees",
+            "get",
+            "--host",
+            "http://127.0.0.1/cardano/api",
+            "--unix-socket",
+            dir.path().join("not-yet.sock").to_str().unwrap(),
+            "--dry-run",
+            "--output-format",
+            "json-compact",
+        ])
+        .unwrap();
+        let mut output = Vec::new();
+        command.write_to(&mut output).unwrap();
+
+        let request: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(request["path"], "/cardano/api/v0/vote/active/committees");
+    }
 }
@@ -2,7 +2,7 @@ mod active;
 mod committees;
 mod plans;
 
-use self::active::Active;
+pub use self::{active::Active, committees::Committees, plans::Plans};
 use crate::jcli_lib::rest::Error;
 use structopt::StructOpt;
 
@@ -1,5 +1,7 @@
-use crate::jcli_lib::rest::{Error, RestArgs};
+use crate::jcli_lib::rest::{unless_dry_run, Error, RestArgs, RestResponse};
 use crate::jcli_lib::utils::OutputFormat;
+use serde_json::Value;
+use std::io;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -12,21 +14,77 @@ pub enum Plans {
         #[structopt(flatten)]
         output_format: OutputFormat,
     },
+    /// Get a single active vote plan
+    Plan {
+        /// hex-encoded id of the vote plan
+        #[structopt(name = "VOTE_PLAN_ID")]
+        id: String,
+        #[structopt(flatten)]
+        args: RestArgs,
+        #[structopt(flatten)]
+        output_format: OutputFormat,
+    },
 }
 
 impl Plans {
-    pub fn exec(self) -> Result<(), Error> {
-        let Plans::Get {
+    /// Lists the active vote plans.
+    pub fn get(args: RestArgs, output_format: OutputFormat) -> Self {
+        Plans::Get {
             args,
             output_format,
-        } = self;
-        let response = args
-            .client()?
-            .get(&["v0", "vote", "active", "plans"])
-            .execute()?
-            .json()?;
-        let formatted = output_format.format_json(response)?;
-        println!("{}", formatted);
+        }
+    }
+
+    /// Fetches the active vote plan with the hex-encoded `id`.
+    pub fn plan(id: String, args: RestArgs, output_format: OutputFormat) -> Self {
+        Plans::Plan {
+            id,
+            args,
+            output_format,
+        }
+    }
+
+    pub fn exec(self) -> Result<(), Error> {
+        let (response, output_format) = self.send()?;
+        if let Some(response) = unless_dry_run(response, &output_format)? {
+            output_format.write_value(io::stdout().lock(), response.json()?)?;
+        }
         Ok(())
     }
+
+    /// Sends the request and returns the answer of the node instead of
+    /// printing it.
+    pub fn response(self) -> Result<Value, Error> {
+        Ok(self.send()?.0.json()?)
+    }
+
+    fn send(self) -> Result<(RestResponse, OutputFormat), Error> {
+        match self {
+            Plans::Get {
+                args,
+                output_format,
+            } => {
+                let response = args
+                    .client()?
+                    .get(&["v0", "vote", "active", "plans"])
+                    .execute()?;
+                Ok((response, output_format))
+            }
+            Plans::Plan {
+                id,
+                args,
+                output_format,
+            } => {
+                let response = match args
+                    .client()?
+                    .get(&["v0", "vote", "active", "plans", &id])
+                    .execute()
+                {
+                    Err(e) if e.is_not_found() => return Err(Error::VotePlanNotFound(id)),
+                    response => response?,
+                };
+                Ok((response, output_format))
+            }
+        }
+    }
 }
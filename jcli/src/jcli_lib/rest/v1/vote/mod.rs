@@ -1,6 +1,7 @@
-use crate::jcli_lib::rest::{Error, RestArgs};
+use crate::jcli_lib::rest::{unless_dry_run, Error, RestArgs};
 use crate::jcli_lib::utils::OutputFormat;
 use crate::utils::AccountId;
+use std::io;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -52,8 +53,7 @@ impl AccountVotes {
                     "account-votes",
                     &self.account_id.to_url_arg(),
                 ])
-                .execute()?
-                .json()?,
+                .execute()?,
             None => self
                 .args
                 .client()?
@@ -64,11 +64,14 @@ impl AccountVotes {
                     "account-votes",
                     &self.account_id.to_url_arg(),
                 ])
-                .execute()?
-                .json()?,
+                .execute()?,
         };
-        let formatted = self.output_format.format_json(response)?;
-        println!("{}", formatted);
+        let response = match unless_dry_run(response, &self.output_format)? {
+            Some(response) => response.json()?,
+            None => return Ok(()),
+        };
+        self.output_format
+            .write_value(io::stdout().lock(), response)?;
         Ok(())
     }
 }
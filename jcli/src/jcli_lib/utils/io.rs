@@ -40,6 +40,36 @@ pub fn open_file_read<P: AsRef<Path>>(path: &Option<P>) -> Result<impl BufRead,
     }
 }
 
+/// write `content` to the given path so that readers only ever see either the
+/// previous file or the complete new content. The file is only readable and
+/// writable by the current user.
+pub fn write_private_file_atomic<P: AsRef<Path>>(path: P, content: &[u8]) -> Result<(), Error> {
+    let path = path.as_ref();
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_path);
+
+    let mut options = std::fs::OpenOptions::new();
+    options.create(true).write(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let result = options.open(&tmp_path).and_then(|mut file| {
+        file.write_all(content)?;
+        file.sync_all()
+    });
+    match result.and_then(|()| std::fs::rename(&tmp_path, path)) {
+        Ok(()) => Ok(()),
+        Err(err) => {
+            let _ = std::fs::remove_file(&tmp_path);
+            Err(err)
+        }
+    }
+}
+
 pub fn path_to_path_buf<P: AsRef<Path>>(path: &Option<P>) -> PathBuf {
     path.as_ref()
         .map(|path| path.as_ref().to_path_buf())
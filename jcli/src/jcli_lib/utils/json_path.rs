@@ -0,0 +1,154 @@
+//! A subset of JSONPath, enough to pick fields out of command output:
+//! `$`, `.name`, `['name']`, `[index]`, `.*` and `[*]`.
+
+use serde_json::Value as JsonValue;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+#[error("invalid JSONPath expression '{expr}': {reason}")]
+pub struct Error {
+    expr: String,
+    reason: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonPath {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Field(String),
+    Index(usize),
+    Wildcard,
+}
+
+impl JsonPath {
+    /// Whether the path names at most one value, i.e. has no wildcard.
+    pub fn is_definite(&self) -> bool {
+        !self.segments.contains(&Segment::Wildcard)
+    }
+
+    /// The value found at the path, or `null` if there is none, for a
+    /// definite path. The array of all the values found otherwise.
+    pub fn select(&self, data: JsonValue) -> JsonValue {
+        let mut values = vec![data];
+        for segment in &self.segments {
+            values = values
+                .into_iter()
+                .flat_map(|value| segment.select(value))
+                .collect();
+        }
+        if self.is_definite() {
+            values.pop().unwrap_or(JsonValue::Null)
+        } else {
+            JsonValue::Array(values)
+        }
+    }
+}
+
+impl Segment {
+    fn select(&self, value: JsonValue) -> Vec<JsonValue> {
+        match (self, value) {
+            (Segment::Field(name), JsonValue::Object(mut object)) => {
+                object.remove(name).into_iter().collect()
+            }
+            (Segment::Index(index), JsonValue::Array(array)) => {
+                array.into_iter().nth(*index).into_iter().collect()
+            }
+            (Segment::Wildcard, JsonValue::Array(array)) => array,
+            (Segment::Wildcard, JsonValue::Object(object)) => {
+                object.into_iter().map(|(_, value)| value).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl FromStr for JsonPath {
+    type Err = Error;
+
+    fn from_str(expr: &str) -> Result<Self, Self::Err> {
+        let error = |reason| Error {
+            expr: expr.to_string(),
+            reason,
+        };
+        let mut rest = expr
+            .trim()
+            .strip_prefix('$')
+            .ok_or_else(|| error("it must start with '$'"))?;
+        let mut segments = Vec::new();
+        while !rest.is_empty() {
+            if rest.starts_with("..") {
+                return Err(error("recursive descent is not supported"));
+            } else if let Some(after) = rest.strip_prefix(".*") {
+                segments.push(Segment::Wildcard);
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix('.') {
+                let len = after
+                    .find(|c: char| c == '.' || c == '[')
+                    .unwrap_or(after.len());
+                if len == 0 {
+                    return Err(error("expected a field name after '.'"));
+                }
+                segments.push(Segment::Field(after[..len].to_string()));
+                rest = &after[len..];
+            } else if let Some(after) = rest.strip_prefix('[') {
+                let end = after.find(']').ok_or_else(|| error("unclosed '['"))?;
+                segments.push(parse_bracket(&after[..end]).ok_or_else(|| {
+                    error("expected '*', an index or a quoted field name between brackets")
+                })?);
+                rest = &after[end + 1..];
+            } else {
+                return Err(error("expected '.' or '['"));
+            }
+        }
+        Ok(Self { segments })
+    }
+}
+
+fn parse_bracket(inner: &str) -> Option<Segment> {
+    let inner = inner.trim();
+    if inner == "*" {
+        return Some(Segment::Wildcard);
+    }
+    for quote in &['\'', '"'] {
+        if let Some(name) = inner
+            .strip_prefix(*quote)
+            .and_then(|name| name.strip_suffix(*quote))
+        {
+            return Some(Segment::Field(name.to_string()));
+        }
+    }
+    inner.parse().ok().map(Segment::Index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn selects_fields_indices_and_wildcards() {
+        let data = json!([
+            { "id": "a", "stake": { "value": 10 } },
+            { "id": "b", "stake": { "value": 20 } },
+        ]);
+        let select = |expr: &str| expr.parse::<JsonPath>().unwrap().select(data.clone());
+
+        assert_eq!(select("$"), data);
+        assert_eq!(select("$[*].id"), json!(["a", "b"]));
+        assert_eq!(select("$[1]['stake'].value"), json!(20));
+        assert_eq!(select("$[*].stake.*"), json!([10, 20]));
+        assert_eq!(select("$[2].id"), JsonValue::Null);
+        assert_eq!(select("$[*].missing"), json!([]));
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        for expr in &["id", "$.", "$[", "$[x]", "$..id", "$id"] {
+            assert!(expr.parse::<JsonPath>().is_err(), "{}", expr);
+        }
+    }
+}
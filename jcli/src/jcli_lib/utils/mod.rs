@@ -1,5 +1,6 @@
 pub mod account_id;
 pub mod io;
+pub mod json_path;
 pub mod key_parser;
 pub mod output_file;
 pub mod output_format;
@@ -37,4 +37,8 @@ impl OutputFile {
             path: self.output.clone().unwrap_or_default(),
         })
     }
+
+    pub fn is_stdout(&self) -> bool {
+        self.output.is_none()
+    }
 }
@@ -1,27 +1,49 @@
+use super::json_path::JsonPath;
 use gtmpl::Value as GtmplValue;
 use serde_json::{Map as JsonMap, Number as JsonNumber, Value as JsonValue};
 use std::fmt::{self, Display, Formatter};
+use std::io::Write;
 use structopt::StructOpt;
 use thiserror::Error;
 
 #[derive(StructOpt)]
 pub struct OutputFormat {
-    /// Format of output data. Possible values: json, yaml.
+    /// Format of output data. Possible values (case-insensitive): json, json-compact, json-lines,
+    /// yaml, csv. json is indented for reading, json-compact prints the whole value on one line.
+    /// With json-lines, each element of a top-level array is printed as compact JSON on its own line.
     /// Any other value is treated as a custom format using values from output data structure.
     /// Syntax is Go text template: https://golang.org/pkg/text/template/.
-    #[structopt(long = "output-format", default_value = "yaml", parse(from_str))]
+    #[structopt(long = "output-format", default_value = "json", parse(from_str))]
     format: FormatVariant,
+    /// Only output the part of the data selected by this JSONPath expression, e.g. `$[*].id`.
+    /// Supports `$`, `.name`, `['name']`, `[index]`, `.*` and `[*]`. Only applied with the
+    /// json output formats, and ignored with a warning otherwise.
+    #[structopt(long)]
+    jsonpath: Option<JsonPath>,
 }
 
 pub enum FormatVariant {
     Yaml,
     Json,
+    JsonCompact,
+    JsonLines,
+    Csv,
     Custom(String),
 }
 
 impl From<FormatVariant> for OutputFormat {
     fn from(format: FormatVariant) -> Self {
-        Self { format }
+        Self {
+            format,
+            jsonpath: None,
+        }
+    }
+}
+
+/// The `--output-format` default, json.
+impl Default for OutputFormat {
+    fn default() -> Self {
+        FormatVariant::Json.into()
     }
 }
 
@@ -30,6 +52,9 @@ impl<'a> From<&'a str> for FormatVariant {
         match format.trim().to_ascii_lowercase().as_str() {
             "yaml" => FormatVariant::Yaml,
             "json" => FormatVariant::Json,
+            "json-compact" => FormatVariant::JsonCompact,
+            "json-lines" => FormatVariant::JsonLines,
+            "csv" => FormatVariant::Csv,
             _ => FormatVariant::Custom(format.to_string()),
         }
     }
@@ -41,6 +66,8 @@ pub enum Error {
     YamlFormattingFailed(#[from] serde_yaml::Error),
     #[error("failed to format output as JSON")]
     JsonFormattingFailed(#[from] serde_json::Error),
+    #[error("failed to format output as CSV: {0}")]
+    CsvFormattingFailed(&'static str),
     #[error("failed to format output as custom format")]
     CustomFormattingFailed(#[from] GtmplError),
 }
@@ -57,16 +84,169 @@ impl Display for GtmplError {
 impl std::error::Error for GtmplError {}
 
 impl OutputFormat {
-    pub fn format_json(&self, data: JsonValue) -> Result<String, Error> {
+    /// Renders `data` in the selected format. This is the single place every
+    /// format variant is handled, so call sites only need to produce a JSON
+    /// value.
+    pub fn format_value(&self, data: JsonValue) -> Result<String, Error> {
+        self.render(self.select(data))
+    }
+
+    fn render(&self, data: JsonValue) -> Result<String, Error> {
         Ok(match self.format {
             FormatVariant::Yaml => serde_yaml::to_string(&data)?,
             FormatVariant::Json => serde_json::to_string_pretty(&data)?,
+            FormatVariant::JsonCompact => serde_json::to_string(&data)?,
+            FormatVariant::JsonLines => {
+                let mut lines = Vec::new();
+                write_json_lines(&mut lines, data)?;
+                lines.pop();
+                // serde_json only ever writes valid UTF-8
+                String::from_utf8(lines).unwrap()
+            }
+            FormatVariant::Csv => json_value_to_csv(data)?,
             FormatVariant::Custom(ref format) => {
                 let gtmpl_value = json_value_to_gtmpl(data);
                 gtmpl::template(format.as_str(), gtmpl_value).map_err(GtmplError)?
             }
         })
     }
+
+    pub fn format_json(&self, data: JsonValue) -> Result<String, Error> {
+        self.format_value(data)
+    }
+
+    /// Writes `data` in the selected format followed by a newline. With
+    /// json-lines the elements of an array are written one at a time instead
+    /// of being rendered into a single string first.
+    pub fn write_value<W: Write>(&self, mut writer: W, data: JsonValue) -> Result<(), Error> {
+        let data = self.select(data);
+        match self.format {
+            FormatVariant::JsonLines => write_json_lines(&mut writer, data),
+            _ => {
+                let formatted = self.render(data)?;
+                writeln!(writer, "{}", formatted).map_err(serde_json::Error::io)?;
+                Ok(())
+            }
+        }
+    }
+}
+
+impl OutputFormat {
+    /// Applies `--jsonpath` to `data`, for the json formats only.
+    fn select(&self, data: JsonValue) -> JsonValue {
+        let jsonpath = match &self.jsonpath {
+            Some(jsonpath) => jsonpath,
+            None => return data,
+        };
+        match self.format {
+            FormatVariant::Json | FormatVariant::JsonCompact | FormatVariant::JsonLines => {
+                jsonpath.select(data)
+            }
+            _ => {
+                eprintln!("warning: --jsonpath is ignored with a non-JSON output format");
+                data
+            }
+        }
+    }
+}
+
+/// Writes each element of an array as compact JSON on its own line. Any other
+/// value falls back to pretty-printed JSON.
+fn write_json_lines<W: Write>(writer: &mut W, data: JsonValue) -> Result<(), Error> {
+    let values = match data {
+        JsonValue::Array(values) => values,
+        value => {
+            serde_json::to_writer_pretty(&mut *writer, &value)?;
+            writer.write_all(b"\n").map_err(serde_json::Error::io)?;
+            return Ok(());
+        }
+    };
+    for value in values {
+        serde_json::to_writer(&mut *writer, &value)?;
+        writer.write_all(b"\n").map_err(serde_json::Error::io)?;
+    }
+    Ok(())
+}
+
+/// Flattens an array of flat objects into CSV rows, with one column per key
+/// sorted by name. An array of plain values
+/// becomes a single `value` column.
+fn json_value_to_csv(value: JsonValue) -> Result<String, Error> {
+    let rows = match value {
+        JsonValue::Array(rows) => rows,
+        object @ JsonValue::Object(_) => vec![object],
+        _ => return Err(Error::CsvFormattingFailed("expected an array or an object")),
+    };
+
+    let header: Vec<String> = match rows.first() {
+        Some(JsonValue::Object(object)) => {
+            let mut keys: Vec<String> = object.keys().cloned().collect();
+            keys.sort();
+            keys
+        }
+        Some(_) => vec!["value".to_string()],
+        None => return Ok(String::new()),
+    };
+
+    let mut csv = csv_record(header.iter().map(String::as_str));
+    for row in rows {
+        let fields = match row {
+            JsonValue::Object(mut object) => {
+                if object.len() != header.len() {
+                    return Err(Error::CsvFormattingFailed(
+                        "rows do not all have the same fields",
+                    ));
+                }
+                header
+                    .iter()
+                    .map(|key| {
+                        object.remove(key).ok_or(Error::CsvFormattingFailed(
+                            "rows do not all have the same fields",
+                        ))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?
+            }
+            _ if header.len() == 1 && header[0] == "value" => vec![row],
+            _ => {
+                return Err(Error::CsvFormattingFailed(
+                    "rows mix objects and plain values",
+                ))
+            }
+        };
+        let fields = fields
+            .into_iter()
+            .map(json_scalar_to_csv)
+            .collect::<Result<Vec<_>, _>>()?;
+        csv.push_str(&csv_record(fields.iter().map(String::as_str)));
+    }
+    Ok(csv)
+}
+
+fn json_scalar_to_csv(value: JsonValue) -> Result<String, Error> {
+    match value {
+        JsonValue::Null => Ok(String::new()),
+        JsonValue::Bool(boolean) => Ok(boolean.to_string()),
+        JsonValue::Number(number) => Ok(number.to_string()),
+        JsonValue::String(string) => Ok(string),
+        JsonValue::Array(_) | JsonValue::Object(_) => Err(Error::CsvFormattingFailed(
+            "nested arrays and objects cannot be flattened",
+        )),
+    }
+}
+
+fn csv_record<'a>(fields: impl Iterator<Item = &'a str>) -> String {
+    let mut record = fields
+        .map(|field| {
+            if field.contains(&[',', '"', '\n', '\r'][..]) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    record.push('\n');
+    record
 }
 
 fn json_value_to_gtmpl(value: JsonValue) -> GtmplValue {
@@ -99,3 +279,36 @@ fn json_object_to_gtmpl(object: JsonMap<String, JsonValue>) -> GtmplValue {
         .collect();
     GtmplValue::Object(values)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn csv_flattens_arrays_of_flat_objects() {
+        let data = json!([
+            { "id": "a", "stake": 10, "note": "x, \"y\"" },
+            { "id": "b", "stake": 20, "note": null },
+        ]);
+        assert_eq!(
+            json_value_to_csv(data).unwrap(),
+            "id,note,stake\na,\"x, \"\"y\"\"\",10\nb,,20\n"
+        );
+
+        assert!(json_value_to_csv(json!([{ "id": "a" }, { "other": "b" }])).is_err());
+        assert!(json_value_to_csv(json!([{ "id": ["a"] }])).is_err());
+    }
+
+    #[test]
+    fn json_compact_is_single_line() {
+        let data = json!({ "id": "a", "shares": [1, 2] });
+        let compact = OutputFormat::from(FormatVariant::from("json-compact"));
+        let pretty = OutputFormat::from(FormatVariant::from("json"));
+        assert_eq!(
+            compact.format_json(data.clone()).unwrap(),
+            r#"{"id":"a","shares":[1,2]}"#
+        );
+        assert!(pretty.format_json(data).unwrap().contains('\n'));
+    }
+}
@@ -1,9 +1,16 @@
 use crate::jcli_lib::utils::io;
+use bech32::FromBase32;
 use jormungandr_lib::crypto::hash::Hash;
 use jormungandr_lib::interfaces::{serde_base64_bytes, VotePlanStatus};
+use serde::de::{
+    value::MapAccessDeserializer, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess,
+    Visitor,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::convert::TryFrom;
+use std::fmt;
+use std::io::Read;
 use std::path::Path;
 use thiserror::Error;
 
@@ -13,14 +20,21 @@ pub enum VotePlanError {
     Io(#[from] std::io::Error),
     #[error(transparent)]
     JsonError(#[from] serde_json::Error),
-    #[error("could not decode vote plan")]
-    VotePlansRead,
     #[error("could not find vote plan with specified id")]
     VotePlanIdNotFound,
     #[error("please specify a correct id for the vote plan")]
     UnclearVotePlan,
+    #[error("encrypted tally has bech32 prefix '{actual}', expected '{expected}'")]
+    EncryptedTallyHrpInvalid {
+        expected: &'static str,
+        actual: String,
+    },
+    #[error("encrypted tally is not valid bech32")]
+    EncryptedTallyBech32Malformed(#[source] bech32::Error),
 }
 
+const ENCRYPTED_TALLY_HRP: &str = "tally";
+
 // Read json-encoded vote plan(s) from file and returns the one
 // with the specified id. If there is only one vote plan in the input
 // the id can be
@@ -28,34 +42,160 @@ pub fn get_vote_plan_by_id<P: AsRef<Path>>(
     vote_plan_file: Option<P>,
     id: Option<&Hash>,
 ) -> Result<VotePlanStatus, VotePlanError> {
-    let value: Value = serde_json::from_reader(io::open_file_read(&vote_plan_file)?)?;
-    match value {
-        Value::Array(vote_plans) => {
-            let plans = vote_plans
-                .into_iter()
-                .map(serde_json::from_value)
-                .collect::<Result<Vec<VotePlanStatus>, serde_json::Error>>()?;
-            match id {
-                Some(id) => plans
-                    .into_iter()
-                    .find(|plan| &plan.id == id)
-                    .ok_or(VotePlanError::VotePlanIdNotFound),
-                None if plans.len() == 1 => Ok(plans.into_iter().next().unwrap()),
-                _ => Err(VotePlanError::UnclearVotePlan),
+    let (mut vote_plan, single) = select_vote_plan(io::open_file_read(&vote_plan_file)?, id)?;
+    decode_bech32_tallies(&mut vote_plan)?;
+    let vote_plan: VotePlanStatus = serde_json::from_value(vote_plan)?;
+    match id {
+        Some(id) if single && &vote_plan.id != id => Err(VotePlanError::VotePlanIdNotFound),
+        _ => Ok(vote_plan),
+    }
+}
+
+/// Reads the vote plan with the given id, or the only one, out of a vote plan
+/// or an array of them. The vote plans of an array are skipped from their
+/// `id` onwards when it is not the one asked for, so that only the selected
+/// one is ever held in memory. The id of a vote plan given on its own is left
+/// for the caller to check, which is told so with `true`.
+fn select_vote_plan<R: Read>(reader: R, id: Option<&Hash>) -> Result<(Value, bool), VotePlanError> {
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    let selection = VotePlanSelector { id }.deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    match selection {
+        Selection::Single(vote_plan) => Ok((vote_plan, true)),
+        Selection::Found(vote_plan) => Ok((vote_plan, false)),
+        Selection::NotFound => Err(VotePlanError::VotePlanIdNotFound),
+        Selection::Unclear => Err(VotePlanError::UnclearVotePlan),
+    }
+}
+
+enum Selection {
+    /// The input was a single vote plan rather than an array.
+    Single(Value),
+    Found(Value),
+    NotFound,
+    Unclear,
+}
+
+struct VotePlanSelector<'a> {
+    id: Option<&'a Hash>,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for VotePlanSelector<'a> {
+    type Value = Selection;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Selection, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'a, 'de> Visitor<'de> for VotePlanSelector<'a> {
+    type Value = Selection;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a vote plan or an array of vote plans")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Selection, A::Error> {
+        Value::deserialize(MapAccessDeserializer::new(map)).map(Selection::Single)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Selection, A::Error> {
+        let selection = match self.id {
+            Some(id) => loop {
+                match seq.next_element_seed(VotePlanWithId { id })? {
+                    Some(Some(plan)) => break Selection::Found(plan),
+                    Some(None) => continue,
+                    None => return Ok(Selection::NotFound),
+                }
+            },
+            None => {
+                let first = seq.next_element::<Value>()?;
+                match (first, seq.next_element::<IgnoredAny>()?) {
+                    (Some(plan), None) => return Ok(Selection::Found(plan)),
+                    _ => Selection::Unclear,
+                }
             }
-        }
-        obj @ Value::Object(_) => {
-            let vote_plan: VotePlanStatus = serde_json::from_value(obj)?;
-            match id {
-                None => Ok(vote_plan),
-                Some(id) if &vote_plan.id == id => Ok(vote_plan),
-                _ => Err(VotePlanError::VotePlanIdNotFound),
+        };
+        // the rest of the array is skipped without being decoded
+        while seq.next_element::<IgnoredAny>()?.is_some() {}
+        Ok(selection)
+    }
+}
+
+/// Reads a vote plan of an array, `None` when it does not have the given id.
+struct VotePlanWithId<'a> {
+    id: &'a Hash,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for VotePlanWithId<'a> {
+    type Value = Option<Value>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Option<Value>, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'a, 'de> Visitor<'de> for VotePlanWithId<'a> {
+    type Value = Option<Value>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a vote plan")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Option<Value>, A::Error> {
+        let mut vote_plan = serde_json::Map::new();
+        let mut id_matches = false;
+        while let Some(key) = map.next_key::<String>()? {
+            let value: Value = map.next_value()?;
+            if key == "id" {
+                id_matches = value
+                    .as_str()
+                    .and_then(|id| id.parse::<Hash>().ok())
+                    .as_ref()
+                    == Some(self.id);
+                if !id_matches {
+                    while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {}
+                    return Ok(None);
+                }
             }
+            vote_plan.insert(key, value);
         }
-        _ => Err(VotePlanError::VotePlansRead),
+        Ok(Some(Value::Object(vote_plan)).filter(|_| id_matches))
     }
 }
 
+// Encrypted tallies may also be given as bech32 strings with the `tally`
+// prefix, as emitted by some tools. They are rewritten to the base64 form
+// used by the vote plan status before it is deserialized.
+fn decode_bech32_tallies(vote_plan: &mut Value) -> Result<(), VotePlanError> {
+    let proposals = match vote_plan.get_mut("proposals").and_then(Value::as_array_mut) {
+        Some(proposals) => proposals,
+        None => return Ok(()),
+    };
+    for proposal in proposals {
+        let encrypted_tally =
+            match proposal.pointer_mut("/tally/Private/state/Encrypted/encrypted_tally") {
+                Some(Value::String(encrypted_tally)) => encrypted_tally,
+                _ => continue,
+            };
+        let (hrp, data, _variant) = match bech32::decode(encrypted_tally) {
+            Ok(decoded) => decoded,
+            // not bech32, left for the base64 deserializer
+            Err(_) => continue,
+        };
+        if hrp != ENCRYPTED_TALLY_HRP {
+            return Err(VotePlanError::EncryptedTallyHrpInvalid {
+                expected: ENCRYPTED_TALLY_HRP,
+                actual: hrp,
+            });
+        }
+        let bytes =
+            Vec::<u8>::from_base32(&data).map_err(VotePlanError::EncryptedTallyBech32Malformed)?;
+        *encrypted_tally = base64::encode(bytes);
+    }
+    Ok(())
+}
+
 #[derive(Debug, Error)]
 pub enum SharesError {
     #[error("I/O error")]
@@ -68,17 +208,19 @@ pub enum SharesError {
     ProposalSharesNotBalanced,
     #[error("insufficient amount of shares for vote plan decryption")]
     InsufficientShares,
+    #[error("{provided} committee member shares were provided, but at least {threshold} are needed for decryption")]
+    BelowThreshold { provided: usize, threshold: usize },
     #[error("invalid binary share data")]
     InvalidBinaryShare,
     #[error("decryption share is not valid")]
     ValidationFailed(#[from] chain_vote::tally::DecryptionError),
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct TallyDecryptShare(#[serde(with = "serde_base64_bytes")] Vec<u8>);
 
 // Set of shares (belonging to a single committee member) for the decryption of a vote plan
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct MemberVotePlanShares(Vec<TallyDecryptShare>);
 
 // Set of decrypt shares (belonging to different committee members)
@@ -94,6 +236,25 @@ impl TryFrom<TallyDecryptShare> for chain_vote::TallyDecryptShare {
     }
 }
 
+impl MemberVotePlanShares {
+    /// Shares of the encrypted proposals, in proposal order.
+    pub fn shares(&self) -> &[TallyDecryptShare] {
+        &self.0
+    }
+}
+
+impl From<MemberVotePlanShares> for Vec<TallyDecryptShare> {
+    fn from(shares: MemberVotePlanShares) -> Self {
+        shares.0
+    }
+}
+
+impl From<&chain_vote::TallyDecryptShare> for TallyDecryptShare {
+    fn from(share: &chain_vote::TallyDecryptShare) -> Self {
+        Self(share.to_bytes())
+    }
+}
+
 impl From<Vec<chain_vote::TallyDecryptShare>> for MemberVotePlanShares {
     fn from(shares: Vec<chain_vote::TallyDecryptShare>) -> Self {
         Self(
@@ -155,3 +316,37 @@ pub fn read_vote_plan_shares_from_file<P: AsRef<Path>>(
 
     Ok(vote_plan_shares)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn vote_plan_is_selected_out_of_an_array() {
+        let ids = ["11", "22", "33"]
+            .iter()
+            .map(|byte| byte.repeat(32).parse::<Hash>().unwrap())
+            .collect::<Vec<_>>();
+        let input = json!([
+            { "id": ids[0].to_string(), "proposals": [] },
+            { "id": ids[1].to_string(), "proposals": [1] },
+        ])
+        .to_string();
+        let select = |id| select_vote_plan(input.as_bytes(), id);
+
+        let (vote_plan, single) = select(Some(&ids[1])).unwrap();
+        assert_eq!(vote_plan["proposals"], json!([1]));
+        assert!(!single);
+        assert!(matches!(
+            select(Some(&ids[2])),
+            Err(VotePlanError::VotePlanIdNotFound)
+        ));
+        assert!(matches!(select(None), Err(VotePlanError::UnclearVotePlan)));
+
+        let single_plan = json!({ "id": ids[0].to_string() }).to_string();
+        let (_, single) = select_vote_plan(single_plan.as_bytes(), None).unwrap();
+        assert!(single);
+        assert!(select_vote_plan(&b"42"[..], None).is_err());
+    }
+}
@@ -4,6 +4,7 @@ use chain_vote::MemberCommunicationKey;
 use rand::rngs::OsRng;
 use rand_chacha::rand_core::SeedableRng;
 use rand_chacha::ChaCha20Rng;
+use serde_json::json;
 use std::{io::Write, path::PathBuf};
 use structopt::StructOpt;
 
@@ -18,6 +19,10 @@ pub struct Generate {
     /// are not sure.
     #[structopt(long = "seed", short = "s", name = "SEED", parse(try_from_str))]
     seed: Option<Seed>,
+
+    /// print the private key together with its public key as a JSON object
+    #[structopt(long)]
+    json: bool,
 }
 
 #[derive(StructOpt, Debug)]
@@ -30,6 +35,10 @@ pub struct ToPublic {
 
     #[structopt(flatten)]
     output_file: OutputFile,
+
+    /// print the public key as a JSON object
+    #[structopt(long)]
+    json: bool,
 }
 
 #[derive(StructOpt, Debug)]
@@ -52,7 +61,21 @@ impl Generate {
         let key = MemberCommunicationKey::new(&mut rng);
 
         let mut output = self.output_file.open()?;
-        writeln!(output, "{}", key.to_bech32_str())?;
+        if self.json {
+            if self.output_file.is_stdout() {
+                eprintln!(
+                    "WARNING: the output contains a committee member secret key, \
+                     make sure standard output is not logged or shared"
+                );
+            }
+            let keys = json!({
+                "secret_key": key.to_bech32_str(),
+                "public_key": key.to_public().to_bech32_str(),
+            });
+            writeln!(output, "{}", serde_json::to_string_pretty(&keys)?)?;
+        } else {
+            writeln!(output, "{}", key.to_bech32_str())?;
+        }
         Ok(())
     }
 }
@@ -64,7 +87,12 @@ impl ToPublic {
         let sk = MemberCommunicationKey::try_from_bech32_str(&line)?.to_public();
 
         let mut output = self.output_file.open()?;
-        writeln!(output, "{}", sk.to_bech32_str())?;
+        if self.json {
+            let key = json!({ "public_key": sk.to_bech32_str() });
+            writeln!(output, "{}", serde_json::to_string_pretty(&key)?)?;
+        } else {
+            writeln!(output, "{}", sk.to_bech32_str())?;
+        }
 
         Ok(())
     }
@@ -43,10 +43,29 @@ pub enum Error {
     InvalidCommitteMemberIndex,
     #[error("failed to read encrypted tally bytes")]
     EncryptedTallyRead,
+    #[error("failed to read encrypted tally: it is {actual} bytes, expected {expected}")]
+    EncryptedTallyLength { actual: usize, expected: usize },
+    #[error("encrypted tally of proposal {index} of vote plan {vote_plan} cannot be parsed")]
+    ProposalTallyInvalid {
+        vote_plan: jormungandr_lib::crypto::hash::Hash,
+        index: u8,
+        #[source]
+        source: Box<Error>,
+    },
     #[error("failed to read decryption key bytes")]
     DecryptionKeyRead,
+    #[error("decryption key is neither bech32 nor a 32 bytes hexadecimal secret key")]
+    DecryptionKeyEncoding,
     #[error("expected encrypted private tally, found {found}")]
     PrivateTallyExpected { found: &'static str },
+    #[error("tally of proposal {index} is still encrypted")]
+    DecryptedTallyExpected { index: u8 },
+    #[error("{labels} option labels given but proposal {index} has {options} options")]
+    OptionLabelsMismatch {
+        index: u8,
+        labels: usize,
+        options: usize,
+    },
     #[error(transparent)]
     TallyError(#[from] chain_vote::tally::TallyError),
     #[error(transparent)]
@@ -67,6 +86,28 @@ pub enum Error {
         source: std::io::Error,
         path: PathBuf,
     },
+    #[error("could not write output file '{path}'")]
+    OutputWriteFailed {
+        #[source]
+        source: std::io::Error,
+        path: PathBuf,
+    },
+    #[error("shares in '{path}' do not match the encrypted tallies of any committee member")]
+    SharesNotFromCommittee { path: PathBuf },
+    #[error("'{first}' and '{second}' hold different shares of committee member {index}")]
+    DuplicateCommitteeMember {
+        index: usize,
+        first: PathBuf,
+        second: PathBuf,
+    },
+    #[error("re-encrypting produces new tallies only the new committee can decrypt, confirm with --confirm-reencrypt")]
+    ReencryptNotConfirmed,
+    #[error("new committee member keys {first} and {second} are the same")]
+    DuplicateNewCommitteeKey { first: usize, second: usize },
+    #[error("new committee member keys are the ones of the current committee")]
+    NewCommitteeUnchanged,
+    #[error("could not build a ballot re-encrypting the tally of proposal {index}")]
+    ReencryptedBallotInvalid { index: u8 },
     #[error("config file corrupted")]
     ConfigFileCorrupted(#[source] serde_yaml::Error),
     #[error("could not open fragment file '{path}'")]
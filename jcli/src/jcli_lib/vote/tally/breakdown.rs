@@ -0,0 +1,189 @@
+use super::Error;
+use crate::jcli_lib::utils::{io, vote, OutputFormat};
+use jormungandr_lib::{
+    crypto::hash::Hash,
+    interfaces::{PrivateTallyState, Tally, VotePlanStatus, VoteProposalStatus},
+};
+use serde::Serialize;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+/// Show the decrypted (or public) tally of each proposal of a vote plan as
+/// the weight of every option and its share of the proposal total.
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct TallyBreakdown {
+    /// The path to json-encoded vote plan with decrypted tallies, e.g. the
+    /// output of `tally decrypt`. If this parameter is not specified, the
+    /// vote plan will be read from standard input.
+    #[structopt(name = "FILE")]
+    vote_plan: Option<PathBuf>,
+    /// The id of the vote plan to show.
+    /// Can be left unspecified if there is only one vote plan in the input
+    #[structopt(long)]
+    vote_plan_id: Option<Hash>,
+    /// The path to a JSON array with the name of each vote option, in option
+    /// order. Options are only identified by their index if not specified.
+    #[structopt(long)]
+    option_labels: Option<PathBuf>,
+    #[structopt(flatten)]
+    output_format: OutputFormat,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VotePlanBreakdown {
+    pub vote_plan_id: String,
+    pub proposals: Vec<ProposalBreakdown>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProposalBreakdown {
+    pub index: u8,
+    pub proposal_id: String,
+    pub total_weight: u64,
+    /// Set when no weight was cast on the proposal, in which case none of
+    /// the options has a percentage.
+    pub no_votes: bool,
+    pub options: Vec<OptionWeight>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OptionWeight {
+    pub index: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub weight: u64,
+    /// Share of the proposal total weight, from 0 to 100.
+    pub percentage: Option<f64>,
+}
+
+impl TallyBreakdown {
+    pub fn exec(&self) -> Result<(), Error> {
+        let vote_plan =
+            vote::get_vote_plan_by_id(self.vote_plan.as_ref(), self.vote_plan_id.as_ref())?;
+        let labels: Option<Vec<String>> = match &self.option_labels {
+            Some(path) => Some(serde_json::from_reader(io::open_file_read(&Some(path))?)?),
+            None => None,
+        };
+
+        let breakdown = VotePlanBreakdown::new(&vote_plan, labels.as_deref())?;
+        println!(
+            "{}",
+            self.output_format
+                .format_json(serde_json::to_value(breakdown)?)?
+        );
+        Ok(())
+    }
+}
+
+impl VotePlanBreakdown {
+    /// Fails if a proposal tally is still encrypted, or if `labels` does not
+    /// name exactly the options of every proposal.
+    pub fn new(vote_plan: &VotePlanStatus, labels: Option<&[String]>) -> Result<Self, Error> {
+        let proposals = vote_plan
+            .proposals
+            .iter()
+            .map(|proposal| ProposalBreakdown::new(proposal, labels))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            vote_plan_id: vote_plan.id.to_string(),
+            proposals,
+        })
+    }
+}
+
+impl ProposalBreakdown {
+    pub fn new(proposal: &VoteProposalStatus, labels: Option<&[String]>) -> Result<Self, Error> {
+        let weights = match &proposal.tally {
+            Tally::Public { result }
+            | Tally::Private {
+                state: PrivateTallyState::Decrypted { result },
+            } => result.results(),
+            Tally::Private {
+                state: PrivateTallyState::Encrypted { .. },
+            } => {
+                return Err(Error::DecryptedTallyExpected {
+                    index: proposal.index,
+                })
+            }
+        };
+        if let Some(labels) = labels {
+            if labels.len() != weights.len() {
+                return Err(Error::OptionLabelsMismatch {
+                    index: proposal.index,
+                    labels: labels.len(),
+                    options: weights.len(),
+                });
+            }
+        }
+
+        Ok(Self::from_weights(
+            proposal.index,
+            proposal.proposal_id.to_string(),
+            &weights,
+            labels,
+        ))
+    }
+
+    fn from_weights(
+        index: u8,
+        proposal_id: String,
+        weights: &[u64],
+        labels: Option<&[String]>,
+    ) -> Self {
+        let total_weight: u64 = weights.iter().sum();
+        let no_votes = total_weight == 0;
+        let options = weights
+            .iter()
+            .enumerate()
+            .map(|(option, &weight)| OptionWeight {
+                index: option as u8,
+                label: labels.map(|labels| labels[option].clone()),
+                weight,
+                percentage: if no_votes {
+                    None
+                } else {
+                    Some(weight as f64 * 100.0 / total_weight as f64)
+                },
+            })
+            .collect();
+        Self {
+            index,
+            proposal_id,
+            total_weight,
+            no_votes,
+            options,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentages_of_total_weight() {
+        let labels = vec!["yes".to_string(), "no".to_string(), "blank".to_string()];
+        let breakdown =
+            ProposalBreakdown::from_weights(0, String::new(), &[30, 10, 0], Some(&labels[..]));
+        assert_eq!(breakdown.total_weight, 40);
+        assert!(!breakdown.no_votes);
+        let percentages: Vec<_> = breakdown
+            .options
+            .iter()
+            .map(|option| option.percentage)
+            .collect();
+        assert_eq!(percentages, vec![Some(75.0), Some(25.0), Some(0.0)]);
+        assert_eq!(breakdown.options[1].label.as_deref(), Some("no"));
+    }
+
+    #[test]
+    fn zero_total_has_no_percentages() {
+        let breakdown = ProposalBreakdown::from_weights(0, String::new(), &[0, 0], None);
+        assert!(breakdown.no_votes);
+        assert!(breakdown
+            .options
+            .iter()
+            .all(|option| option.percentage.is_none() && option.label.is_none()));
+    }
+}
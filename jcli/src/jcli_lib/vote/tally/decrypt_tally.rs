@@ -1,12 +1,12 @@
-use super::Error;
+use super::{decode_encrypted_tally, decryption_shares::merge_committee_shares, Error};
 use crate::jcli_lib::utils::{
-    vote::{self, SharesError},
+    vote::{self, SharesError, VotePlanDecryptShares},
     OutputFormat,
 };
-use chain_vote::tally::{batch_decrypt, EncryptedTally};
+use chain_vote::tally::batch_decrypt;
 use jormungandr_lib::{
     crypto::hash::Hash,
-    interfaces::{PrivateTallyState, Tally},
+    interfaces::{PrivateTallyState, Tally, VotePlanStatus},
 };
 use rayon::prelude::*;
 use serde::Serialize;
@@ -37,6 +37,27 @@ pub struct TallyVotePlanWithAllShares {
     output_format: OutputFormat,
 }
 
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct TallyDecrypt {
+    /// The path to json-encoded vote plan to decrypt. If this parameter is not
+    /// specified, the vote plan will be read from the standard
+    /// input.
+    #[structopt(long)]
+    vote_plan: Option<PathBuf>,
+    /// The id of the vote plan to decrypt.
+    /// Can be left unspecified if there is only one vote plan in the input
+    #[structopt(long)]
+    vote_plan_id: Option<Hash>,
+    /// The paths to the decryption shares of each committee member, as
+    /// produced by `decryption-shares`. Every member of the committee of the
+    /// vote plan has to provide its shares.
+    #[structopt(long = "share", required = true)]
+    shares: Vec<PathBuf>,
+    #[structopt(flatten)]
+    output_format: OutputFormat,
+}
+
 #[derive(Serialize)]
 struct Output {
     result: Vec<u64>,
@@ -44,7 +65,7 @@ struct Output {
 
 impl TallyVotePlanWithAllShares {
     pub fn exec(&self) -> Result<(), Error> {
-        let mut vote_plan =
+        let vote_plan =
             vote::get_vote_plan_by_id(self.vote_plan.as_ref(), self.vote_plan_id.as_ref())?;
         let shares: Vec<Vec<chain_vote::TallyDecryptShare>> =
             vote::read_vote_plan_shares_from_file(
@@ -53,64 +74,24 @@ impl TallyVotePlanWithAllShares {
                 Some(self.threshold),
             )?
             .try_into()?;
-        let mut max_stake = 0;
-        // We need a first iteration to get the max stake used
-        for proposal in &mut vote_plan.proposals {
-            match &proposal.tally {
-                Tally::Private {
-                    state: PrivateTallyState::Encrypted { total_stake, .. },
-                } => {
-                    max_stake = std::cmp::max(u64::from(*total_stake), max_stake);
-                }
-                other => {
-                    let found = match other {
-                        Tally::Public { .. } => "public tally",
-                        Tally::Private { .. } => "private decrypted tally",
-                    };
-                    return Err(Error::PrivateTallyExpected { found });
-                }
-            }
-        }
+        let vote_plan = decrypt_vote_plan(vote_plan, shares)?;
 
-        let committee_member_keys = vote_plan.committee_member_keys.clone();
-
-        let validated_tallies = (&vote_plan.proposals)
-            .into_par_iter()
-            .zip(shares.into_par_iter())
-            .map(|(proposal, shares)| {
-                let encrypted_tally = match &proposal.tally {
-                    Tally::Private {
-                        state:
-                            PrivateTallyState::Encrypted {
-                                encrypted_tally, ..
-                            },
-                    } => encrypted_tally,
-                    _ => unreachable!("expected encrypted private tally"),
-                };
+        let output = self
+            .output_format
+            .format_json(serde_json::to_value(vote_plan)?)?;
+        println!("{}", output);
 
-                let encrypted_tally = EncryptedTally::from_bytes(encrypted_tally.as_ref())
-                    .ok_or(Error::EncryptedTallyRead)?;
-
-                encrypted_tally
-                    .validate_partial_decryptions(&committee_member_keys, &shares)
-                    .map_err(SharesError::ValidationFailed)
-                    .map_err(Error::SharesError)
-            })
-            .collect::<Result<Vec<_>, _>>()?;
-
-        let decrypted_tallies = batch_decrypt(validated_tallies)?;
-
-        for (proposal, decrypted_tally) in vote_plan
-            .proposals
-            .iter_mut()
-            .zip(decrypted_tallies.into_iter())
-        {
-            proposal.tally = Tally::Private {
-                state: PrivateTallyState::Decrypted {
-                    result: decrypted_tally.into(),
-                },
-            }
-        }
+        Ok(())
+    }
+}
+
+impl TallyDecrypt {
+    pub fn exec(&self) -> Result<(), Error> {
+        let vote_plan =
+            vote::get_vote_plan_by_id(self.vote_plan.as_ref(), self.vote_plan_id.as_ref())?;
+        let shares = merge_committee_shares(&vote_plan, &self.shares)?;
+        let shares = committee_shares(&vote_plan, shares)?;
+        let vote_plan = decrypt_vote_plan(vote_plan, shares)?;
 
         let output = self
             .output_format
@@ -120,3 +101,92 @@ impl TallyVotePlanWithAllShares {
         Ok(())
     }
 }
+
+/// Checks every member of the committee of `vote_plan` provided shares for
+/// each of its proposals, in shares merged as `merge-shares --vote-plan`
+/// does.
+pub(super) fn committee_shares(
+    vote_plan: &VotePlanStatus,
+    shares: VotePlanDecryptShares,
+) -> Result<Vec<Vec<chain_vote::TallyDecryptShare>>, Error> {
+    let shares: Vec<Vec<chain_vote::TallyDecryptShare>> = shares.try_into()?;
+    let committee_size = vote_plan.committee_member_keys.len();
+    let provided = shares.first().map_or(0, Vec::len);
+    if provided < committee_size {
+        return Err(SharesError::BelowThreshold {
+            provided,
+            threshold: committee_size,
+        }
+        .into());
+    }
+    if shares.len() != vote_plan.proposals.len() {
+        return Err(SharesError::InsufficientShares.into());
+    }
+    Ok(shares)
+}
+
+pub(super) fn decrypt_vote_plan(
+    mut vote_plan: VotePlanStatus,
+    shares: Vec<Vec<chain_vote::TallyDecryptShare>>,
+) -> Result<VotePlanStatus, Error> {
+    let mut max_stake = 0;
+    // We need a first iteration to get the max stake used
+    for proposal in &mut vote_plan.proposals {
+        match &proposal.tally {
+            Tally::Private {
+                state: PrivateTallyState::Encrypted { total_stake, .. },
+            } => {
+                max_stake = std::cmp::max(u64::from(*total_stake), max_stake);
+            }
+            other => {
+                let found = match other {
+                    Tally::Public { .. } => "public tally",
+                    Tally::Private { .. } => "private decrypted tally",
+                };
+                return Err(Error::PrivateTallyExpected { found });
+            }
+        }
+    }
+
+    let committee_member_keys = vote_plan.committee_member_keys.clone();
+
+    let validated_tallies = (&vote_plan.proposals)
+        .into_par_iter()
+        .zip(shares.into_par_iter())
+        .map(|(proposal, shares)| {
+            let encrypted_tally = match &proposal.tally {
+                Tally::Private {
+                    state:
+                        PrivateTallyState::Encrypted {
+                            encrypted_tally, ..
+                        },
+                } => encrypted_tally,
+                _ => unreachable!("expected encrypted private tally"),
+            };
+
+            let encrypted_tally =
+                decode_encrypted_tally(encrypted_tally.as_ref(), proposal.options.len())?;
+
+            encrypted_tally
+                .validate_partial_decryptions(&committee_member_keys, &shares)
+                .map_err(SharesError::ValidationFailed)
+                .map_err(Error::SharesError)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let decrypted_tallies = batch_decrypt(validated_tallies)?;
+
+    for (proposal, decrypted_tally) in vote_plan
+        .proposals
+        .iter_mut()
+        .zip(decrypted_tallies.into_iter())
+    {
+        proposal.tally = Tally::Private {
+            state: PrivateTallyState::Decrypted {
+                result: decrypted_tally.into(),
+            },
+        }
+    }
+
+    Ok(vote_plan)
+}
@@ -1,57 +1,332 @@
-use super::Error;
-use crate::jcli_lib::utils::io;
-use crate::jcli_lib::utils::vote::{self, MemberVotePlanShares, VotePlanDecryptShares};
+use super::{decode_encrypted_tally, Error};
+use crate::jcli_lib::utils::vote::{
+    self, MemberVotePlanShares, SharesError, TallyDecryptShare, VotePlanDecryptShares,
+};
+use crate::jcli_lib::utils::{io, OutputFormat};
 use chain_crypto::bech32::Bech32;
-use chain_vote::tally::{EncryptedTally, OpeningVoteKey};
+use chain_vote::{committee::MemberPublicKey, tally::OpeningVoteKey};
 use jormungandr_lib::crypto::hash::Hash;
-use jormungandr_lib::interfaces::{PrivateTallyState, Tally};
+use jormungandr_lib::interfaces::{PrivateTallyState, Tally, VotePlanStatus};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeMap;
 use std::convert::TryFrom;
-use std::path::PathBuf;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
 use structopt::StructOpt;
 
 /// Create decryption shares for all proposals in a vote plan.
 ///
-/// The decryption share data will be printed in hexadecimal encoding
-/// on standard output.
+/// The decryption share data will be printed in the `--output-format` format
+/// on standard output, or written to the file given with `--output`.
+/// `decrypt` and `merge-shares` read them as JSON, which `json-compact`
+/// prints on a single line.
+///
+/// When several vote plan files or a directory of them are given, a single
+/// document is produced instead, listing the vote plan, proposal index,
+/// tally state and share of every proposal, in the `--output-format` format.
+/// `--verbose` produces that document for a single vote plan as well, with
+/// details about each share next to it.
+///
+/// `--raw-binary` writes the shares as bytes instead, for tools that would
+/// rather not decode hexadecimal. The output starts with the 4 bytes
+/// `JDS\x01`, the last one being the format version, followed by one record
+/// per proposal of every vote plan, up to the end of the output:
+///
+/// * the vote plan id, 32 bytes;
+/// * the proposal index, 1 byte;
+/// * the tally state, 1 byte: 0 for public, 1 for decrypted and 2 for
+///   encrypted;
+/// * the length of the share, 4 bytes, big endian;
+/// * the share bytes, only present for encrypted tallies.
 #[derive(StructOpt)]
 #[structopt(rename_all = "kebab-case")]
 pub struct TallyGenerateVotePlanDecryptionShares {
     /// The path to json-encoded vote plan to decrypt. If this parameter is not
     /// specified, the vote plan will be read from standard input.
+    /// Can be repeated or point to a directory, in which case every `.json`
+    /// file in it is read as a vote plan.
     #[structopt(long)]
-    vote_plan: Option<PathBuf>,
+    vote_plan: Vec<PathBuf>,
     /// The id of the vote plan to decrypt.
-    /// Can be left unspecified if there is only one vote plan in the input
+    /// Can be left unspecified if there is only one vote plan in the input.
+    /// Only used when reading a single vote plan file.
     #[structopt(long)]
     vote_plan_id: Option<Hash>,
-    /// The path to bech32-encoded decryption key.
+    /// The path to the decryption key, either bech32 or hexadecimal encoded.
     #[structopt(long)]
     key: PathBuf,
+    /// The path to write the decryption shares to. The file is replaced
+    /// atomically and only readable by the current user. If this parameter
+    /// is not specified, the shares will be printed on standard output.
+    #[structopt(long)]
+    output: Option<PathBuf>,
+    /// Also print the committee index of the key owner, the number of
+    /// options and whether the share proofs are valid for every share
+    #[structopt(long)]
+    verbose: bool,
+    /// Write the shares as length-prefixed binary records, as described
+    /// above, instead of JSON
+    #[structopt(long, conflicts_with = "verbose")]
+    raw_binary: bool,
+    #[structopt(flatten)]
+    output_format: OutputFormat,
 }
 
+const RAW_SHARES_MAGIC: &[u8; 4] = b"JDS\x01";
+
 /// Merge multiple sets of shares in a single object to be used in the
 /// decryption of a vote plan.
 ///
 /// The data will be printed in hexadecimal encoding
 /// on standard output.
+///
+/// When the vote plan the shares were produced for is given, the shares are
+/// ordered by the committee index of their owner, identical files are only
+/// counted once and shares of keys outside the committee are rejected.
 #[derive(StructOpt)]
 #[structopt(rename_all = "kebab-case")]
 pub struct MergeShares {
+    /// The path to json-encoded vote plan the shares were produced for. If
+    /// only `--vote-plan-id` is given, the vote plan will be read from
+    /// standard input.
+    #[structopt(long)]
+    vote_plan: Option<PathBuf>,
+    /// The id of the vote plan.
+    /// Can be left unspecified if there is only one vote plan in the input
+    #[structopt(long)]
+    vote_plan_id: Option<Hash>,
     /// The path to the shares to merge
     shares: Vec<PathBuf>,
 }
 
 impl TallyGenerateVotePlanDecryptionShares {
     pub fn exec(&self) -> Result<(), Error> {
-        let vote_plan =
-            vote::get_vote_plan_by_id(self.vote_plan.as_ref(), self.vote_plan_id.as_ref())?;
         let line = io::read_line(&Some(&self.key))?;
-        let decryption_key = OpeningVoteKey::try_from_bech32_str(&line)?;
+        let decryption_key = parse_decryption_key(&line)?;
+
+        if self.raw_binary {
+            return self.exec_raw(&decryption_key);
+        }
+        match self.vote_plan.as_slice() {
+            [] => self.exec_single(None, &decryption_key),
+            [path] if !path.is_dir() => self.exec_single(Some(path), &decryption_key),
+            paths => self.exec_many(paths, &decryption_key),
+        }
+    }
 
-        let shares = vote_plan
-            .proposals
+    fn exec_single(
+        &self,
+        vote_plan: Option<&PathBuf>,
+        decryption_key: &OpeningVoteKey,
+    ) -> Result<(), Error> {
+        let vote_plan = vote::get_vote_plan_by_id(vote_plan, self.vote_plan_id.as_ref())?;
+        if self.verbose {
+            let entries = self.entries(vote_plan, decryption_key)?;
+            return self.write_shares(json!(entries));
+        }
+        let shares = proposal_shares(vote_plan, decryption_key, false)?
             .into_iter()
-            .filter_map(|prop| match prop.tally {
+            .filter_map(|proposal| proposal.share)
+            .collect::<Vec<_>>();
+        let shares = MemberVotePlanShares::from(shares);
+        self.write_shares(serde_json::to_value(shares)?)
+    }
+
+    fn exec_many(&self, paths: &[PathBuf], decryption_key: &OpeningVoteKey) -> Result<(), Error> {
+        let mut entries = Vec::new();
+        for path in vote_plan_files(paths)? {
+            let vote_plan = vote::get_vote_plan_by_id(Some(&path), None)?;
+            entries.extend(self.entries(vote_plan, decryption_key)?);
+        }
+        self.write_shares(json!(entries))
+    }
+
+    fn exec_raw(&self, decryption_key: &OpeningVoteKey) -> Result<(), Error> {
+        let vote_plans = match self.vote_plan.as_slice() {
+            [] => vec![vote::get_vote_plan_by_id(None, self.vote_plan_id.as_ref())?],
+            [path] if !path.is_dir() => vec![vote::get_vote_plan_by_id(
+                Some(path),
+                self.vote_plan_id.as_ref(),
+            )?],
+            paths => vote_plan_files(paths)?
+                .iter()
+                .map(|path| vote::get_vote_plan_by_id(Some(path), None))
+                .collect::<Result<_, _>>()?,
+        };
+
+        let mut bytes = RAW_SHARES_MAGIC.to_vec();
+        for vote_plan in vote_plans {
+            let vote_plan_id = vote_plan.id.into_hash();
+            for proposal in proposal_shares(vote_plan, decryption_key, false)? {
+                let share = proposal
+                    .share
+                    .map(|share| share.to_bytes())
+                    .unwrap_or_default();
+                bytes.extend_from_slice(vote_plan_id.as_ref());
+                bytes.push(proposal.index);
+                bytes.push(proposal.state.raw_code());
+                bytes.extend_from_slice(&(share.len() as u32).to_be_bytes());
+                bytes.extend_from_slice(&share);
+            }
+        }
+        match &self.output {
+            Some(path) => self.write_file(path, &bytes),
+            None => Ok(std::io::stdout().write_all(&bytes)?),
+        }
+    }
+
+    fn entries(
+        &self,
+        vote_plan: VotePlanStatus,
+        decryption_key: &OpeningVoteKey,
+    ) -> Result<Vec<serde_json::Value>, Error> {
+        let vote_plan_id = vote_plan.id.to_string();
+        let member_key = decryption_key.to_public().to_bytes();
+        let member_index = vote_plan
+            .committee_member_keys
+            .iter()
+            .position(|key| key.to_bytes() == member_key);
+        let entries = proposal_shares(vote_plan, decryption_key, self.verbose)?
+            .into_iter()
+            .map(|proposal| {
+                let mut entry = json!({
+                    "vote_plan": vote_plan_id,
+                    "proposal": proposal.index,
+                    "state": proposal.state,
+                    "share": proposal.share.as_ref().map(TallyDecryptShare::from),
+                });
+                if self.verbose {
+                    entry["details"] = match &proposal.share {
+                        Some(share) => json!({
+                            "committee_member_index": member_index,
+                            "options": proposal.options,
+                            "bytes": share.to_bytes().len(),
+                            "proof_valid": proposal.proof_valid,
+                        }),
+                        None => serde_json::Value::Null,
+                    };
+                }
+                entry
+            })
+            .collect();
+        Ok(entries)
+    }
+
+    /// Writes the shares in the `--output-format` format.
+    fn write_shares(&self, entries: serde_json::Value) -> Result<(), Error> {
+        match &self.output {
+            Some(path) => {
+                let mut content = Vec::new();
+                self.output_format.write_value(&mut content, entries)?;
+                self.write_file(path, &content)
+            }
+            None => {
+                self.output_format
+                    .write_value(std::io::stdout().lock(), entries)?;
+                Ok(())
+            }
+        }
+    }
+
+    fn write_file(&self, path: &Path, content: &[u8]) -> Result<(), Error> {
+        io::write_private_file_atomic(path, content).map_err(|source| Error::OutputWriteFailed {
+            source,
+            path: path.to_path_buf(),
+        })
+    }
+}
+
+/// The paths given on the command line, with directories replaced by the
+/// `.json` files they contain, in name order.
+fn vote_plan_files(paths: &[PathBuf]) -> Result<Vec<PathBuf>, Error> {
+    let mut files = Vec::new();
+    for path in paths {
+        if !path.is_dir() {
+            files.push(path.clone());
+            continue;
+        }
+        let mut dir_files = std::fs::read_dir(path)
+            .map_err(|source| input_invalid(source, path))?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|source| input_invalid(source, path))?;
+        dir_files.retain(|file| file.is_file() && file.extension() == Some("json".as_ref()));
+        dir_files.sort();
+        files.extend(dir_files);
+    }
+    Ok(files)
+}
+
+fn input_invalid(source: std::io::Error, path: &Path) -> Error {
+    Error::InputInvalid {
+        source,
+        path: path.to_path_buf(),
+    }
+}
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum TallyState {
+    Public,
+    Decrypted,
+    Encrypted,
+}
+
+impl TallyState {
+    /// Code of the state in `--raw-binary` records.
+    fn raw_code(self) -> u8 {
+        match self {
+            TallyState::Public => 0,
+            TallyState::Decrypted => 1,
+            TallyState::Encrypted => 2,
+        }
+    }
+}
+
+struct ProposalShare {
+    index: u8,
+    state: TallyState,
+    options: usize,
+    /// Only set for encrypted tallies.
+    share: Option<chain_vote::TallyDecryptShare>,
+    /// Whether the share proofs check against the public key of the member
+    /// in the committee of the vote plan, false for keys which are not part
+    /// of it. Only computed when asked for.
+    proof_valid: Option<bool>,
+}
+
+/// Index, tally state and, for encrypted tallies, decryption share of every
+/// proposal of the vote plan.
+fn proposal_shares(
+    vote_plan: VotePlanStatus,
+    decryption_key: &OpeningVoteKey,
+    verify: bool,
+) -> Result<Vec<ProposalShare>, Error> {
+    let vote_plan_id = vote_plan.id;
+    // the key of the member in the committee of the vote plan, shares are
+    // checked against it rather than against the key derived from ours
+    let own_key = decryption_key.to_public().to_bytes();
+    let member_key = vote_plan
+        .committee_member_keys
+        .into_iter()
+        .find(|key| key.to_bytes() == own_key);
+    vote_plan
+        .proposals
+        .into_iter()
+        .map(|prop| {
+            let mut proposal = ProposalShare {
+                index: prop.index,
+                state: TallyState::Public,
+                options: prop.options.len(),
+                share: None,
+                proof_valid: None,
+            };
+            match prop.tally {
+                Tally::Public { .. } => {}
+                Tally::Private {
+                    state: PrivateTallyState::Decrypted { .. },
+                } => proposal.state = TallyState::Decrypted,
                 Tally::Private {
                     state:
                         PrivateTallyState::Encrypted {
@@ -59,29 +334,158 @@ impl TallyGenerateVotePlanDecryptionShares {
                         },
                 } => {
                     let encrypted_tally =
-                        EncryptedTally::from_bytes(&encrypted_tally.into_bytes())?;
-                    Some(encrypted_tally.partial_decrypt(&mut rand::thread_rng(), &decryption_key))
+                        decode_encrypted_tally(&encrypted_tally.into_bytes(), proposal.options)
+                            .map_err(|source| Error::ProposalTallyInvalid {
+                                vote_plan: vote_plan_id,
+                                index: prop.index,
+                                source: Box::new(source),
+                            })?;
+                    let share =
+                        encrypted_tally.partial_decrypt(&mut rand::thread_rng(), decryption_key);
+                    if verify {
+                        let valid = member_key.as_ref().map_or(false, |member_key| {
+                            encrypted_tally
+                                .validate_partial_decryptions(
+                                    std::slice::from_ref(member_key),
+                                    std::slice::from_ref(&share),
+                                )
+                                .is_ok()
+                        });
+                        proposal.proof_valid = Some(valid);
+                    }
+                    proposal.state = TallyState::Encrypted;
+                    proposal.share = Some(share);
                 }
-                _ => None,
-            })
-            .collect::<Vec<_>>();
-        println!(
-            "{}",
-            serde_json::to_value(MemberVotePlanShares::from(shares))?
-        );
-        Ok(())
+            }
+            Ok(proposal)
+        })
+        .collect()
+}
+
+fn parse_decryption_key(line: &str) -> Result<OpeningVoteKey, Error> {
+    if let Ok(key) = OpeningVoteKey::try_from_bech32_str(line) {
+        return Ok(key);
     }
+    let mut bytes = [0u8; 32];
+    hex::decode_to_slice(line, &mut bytes).map_err(|_| Error::DecryptionKeyEncoding)?;
+    OpeningVoteKey::from_bytes(&bytes).ok_or(Error::DecryptionKeyEncoding)
 }
 
 impl MergeShares {
     pub fn exec(&self) -> Result<(), Error> {
-        let shares = self
-            .shares
-            .iter()
-            .map(|path| Ok(serde_json::from_reader(io::open_file_read(&Some(path))?)?))
-            .collect::<Result<Vec<MemberVotePlanShares>, Error>>()?;
-        let vote_plan_shares = VotePlanDecryptShares::try_from(shares)?;
+        let vote_plan_shares = if self.vote_plan.is_some() || self.vote_plan_id.is_some() {
+            let vote_plan =
+                vote::get_vote_plan_by_id(self.vote_plan.as_ref(), self.vote_plan_id.as_ref())?;
+            merge_committee_shares(&vote_plan, &self.shares)?
+        } else {
+            merge_shares(&self.shares)?
+        };
         println!("{}", serde_json::to_string(&vote_plan_shares)?);
         Ok(())
     }
 }
+
+/// Merges the shares of each committee member, as produced by
+/// `decryption-shares`, into the shares of each proposal.
+fn merge_shares(paths: &[PathBuf]) -> Result<VotePlanDecryptShares, Error> {
+    let shares = paths
+        .iter()
+        .map(|path| Ok(serde_json::from_reader(io::open_file_read(&Some(path))?)?))
+        .collect::<Result<Vec<MemberVotePlanShares>, Error>>()?;
+    Ok(VotePlanDecryptShares::try_from(shares)?)
+}
+
+/// Merges the shares of the committee members of `vote_plan` in the order of
+/// their committee index, as `validate_partial_decryptions` expects them.
+/// Identical files are only counted once.
+pub(super) fn merge_committee_shares(
+    vote_plan: &VotePlanStatus,
+    paths: &[PathBuf],
+) -> Result<VotePlanDecryptShares, Error> {
+    let encrypted_tallies = vote_plan
+        .proposals
+        .iter()
+        .filter_map(|proposal| match &proposal.tally {
+            Tally::Private {
+                state:
+                    PrivateTallyState::Encrypted {
+                        encrypted_tally, ..
+                    },
+            } => Some((encrypted_tally.as_ref(), proposal.options.len())),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if encrypted_tallies.is_empty() {
+        return Err(SharesError::Empty.into());
+    }
+
+    let mut members: BTreeMap<usize, (&PathBuf, MemberVotePlanShares)> = BTreeMap::new();
+    for path in paths {
+        let member_shares: MemberVotePlanShares =
+            serde_json::from_reader(io::open_file_read(&Some(path))?)?;
+        if member_shares.shares().len() != encrypted_tallies.len() {
+            return Err(SharesError::ProposalSharesNotBalanced.into());
+        }
+        let index = committee_index(
+            &encrypted_tallies,
+            &vote_plan.committee_member_keys,
+            &member_shares,
+        )?
+        .ok_or_else(|| Error::SharesNotFromCommittee { path: path.clone() })?;
+        match members.get(&index) {
+            Some((_, known)) if *known == member_shares => {}
+            Some((first, _)) => {
+                return Err(Error::DuplicateCommitteeMember {
+                    index,
+                    first: first.to_path_buf(),
+                    second: path.clone(),
+                })
+            }
+            None => {
+                members.insert(index, (path, member_shares));
+            }
+        }
+    }
+
+    Ok(VotePlanDecryptShares::try_from(
+        members
+            .into_iter()
+            .map(|(_, (_, shares))| shares)
+            .collect::<Vec<_>>(),
+    )?)
+}
+
+/// Index of the committee member whose key the proofs of every share check
+/// against. Shares produced for other tallies match no member.
+fn committee_index(
+    encrypted_tallies: &[(&[u8], usize)],
+    committee_member_keys: &[MemberPublicKey],
+    member_shares: &MemberVotePlanShares,
+) -> Result<Option<usize>, Error> {
+    let shares = member_shares
+        .shares()
+        .iter()
+        .cloned()
+        .map(chain_vote::TallyDecryptShare::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+    let is_valid = |key: &MemberPublicKey| -> Result<bool, Error> {
+        for ((encrypted_tally, options), share) in encrypted_tallies.iter().zip(&shares) {
+            let valid = decode_encrypted_tally(encrypted_tally, *options)?
+                .validate_partial_decryptions(
+                    std::slice::from_ref(key),
+                    std::slice::from_ref(share),
+                )
+                .is_ok();
+            if !valid {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    };
+    for (index, key) in committee_member_keys.iter().enumerate() {
+        if is_valid(key)? {
+            return Ok(Some(index));
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,76 @@
+use super::{decode_encrypted_tally, Error};
+use crate::jcli_lib::utils::{vote, OutputFormat};
+use jormungandr_lib::{
+    crypto::hash::Hash,
+    interfaces::{PrivateTallyState, Tally},
+};
+use serde_json::json;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+/// Show the parameters of the tallies of a vote plan, e.g. to check the
+/// right vote plan is about to be decrypted. No decryption key is needed.
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct TallyInfo {
+    /// The path to json-encoded vote plan. If this parameter is not
+    /// specified, the vote plan will be read from standard input.
+    #[structopt(name = "FILE")]
+    vote_plan: Option<PathBuf>,
+    /// The id of the vote plan to inspect.
+    /// Can be left unspecified if there is only one vote plan in the input
+    #[structopt(long)]
+    vote_plan_id: Option<Hash>,
+    #[structopt(flatten)]
+    output_format: OutputFormat,
+}
+
+impl TallyInfo {
+    pub fn exec(&self) -> Result<(), Error> {
+        let vote_plan =
+            vote::get_vote_plan_by_id(self.vote_plan.as_ref(), self.vote_plan_id.as_ref())?;
+
+        let proposals = vote_plan
+            .proposals
+            .iter()
+            .map(|proposal| {
+                let options = proposal.options.len();
+                let tally = match &proposal.tally {
+                    Tally::Public { .. } => json!({ "type": "public" }),
+                    Tally::Private {
+                        state: PrivateTallyState::Decrypted { .. },
+                    } => json!({ "type": "private decrypted" }),
+                    Tally::Private {
+                        state:
+                            PrivateTallyState::Encrypted {
+                                encrypted_tally,
+                                total_stake,
+                            },
+                    } => {
+                        // only checks the tally can be loaded
+                        decode_encrypted_tally(encrypted_tally.as_ref(), options)?;
+                        json!({
+                            "type": "private encrypted",
+                            "encrypted_tally_bytes": encrypted_tally.as_ref().len(),
+                            "total_stake": u64::from(*total_stake),
+                        })
+                    }
+                };
+                Ok(json!({
+                    "index": proposal.index,
+                    "proposal_id": proposal.proposal_id.to_string(),
+                    "options": options,
+                    "tally": tally,
+                }))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let info = json!({
+            "vote_plan_id": vote_plan.id.to_string(),
+            "committee_members": vote_plan.committee_member_keys.len(),
+            "proposals": proposals,
+        });
+        println!("{}", self.output_format.format_json(info)?);
+        Ok(())
+    }
+}
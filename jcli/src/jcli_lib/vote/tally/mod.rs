@@ -1,7 +1,13 @@
+mod breakdown;
 mod decrypt_tally;
 mod decryption_shares;
+mod info;
+mod reencrypt;
+mod report;
+mod verify_share;
 
 use super::Error;
+use chain_vote::{tally::EncryptedTally, Crs, ElectionPublicKey};
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -20,6 +26,30 @@ pub enum Tally {
     /// The decrypted tally data will be printed in hexadecimal encoding
     /// on standard output.
     DecryptResults(decrypt_tally::TallyVotePlanWithAllShares),
+    /// Decrypt all proposals in a vote plan directly from the decryption
+    /// shares of each committee member.
+    ///
+    /// The decrypted tally data will be printed in hexadecimal encoding
+    /// on standard output.
+    Decrypt(decrypt_tally::TallyDecrypt),
+    /// Show the weight of each option of the decrypted tallies of a vote
+    /// plan, with its percentage of the proposal total.
+    Breakdown(breakdown::TallyBreakdown),
+    /// Show the number of options and committee size of the tallies of a
+    /// vote plan without decrypting them.
+    Info(info::TallyInfo),
+    /// Check the decryption shares of a committee member against the
+    /// encrypted tallies of a vote plan.
+    VerifyShare(verify_share::VerifyShare),
+    /// Decrypt the tallies of a vote plan into a report holding the vote
+    /// plan, the decrypted tallies, the encrypted tally hashes and the
+    /// shares used, with a hash of its content and optionally a signature.
+    Report(report::TallyReport),
+    /// Produce new tallies of a vote plan encrypted to a replacement
+    /// committee, decrypting the current ones with the shares of the current
+    /// committee. The new tallies are not the on-chain tallies of the vote
+    /// plan.
+    Reencrypt(reencrypt::TallyReencrypt),
 }
 
 impl Tally {
@@ -28,6 +58,55 @@ impl Tally {
             Tally::DecryptionShares(cmd) => cmd.exec(),
             Tally::DecryptResults(cmd) => cmd.exec(),
             Tally::MergeShares(cmd) => cmd.exec(),
+            Tally::Decrypt(cmd) => cmd.exec(),
+            Tally::Breakdown(cmd) => cmd.exec(),
+            Tally::Info(cmd) => cmd.exec(),
+            Tally::VerifyShare(cmd) => cmd.exec(),
+            Tally::Report(cmd) => cmd.exec(),
+            Tally::Reencrypt(cmd) => cmd.exec(),
+        }
+    }
+}
+
+fn decode_encrypted_tally(bytes: &[u8], options: usize) -> Result<EncryptedTally, Error> {
+    EncryptedTally::from_bytes(bytes).ok_or_else(|| {
+        let expected = encrypted_tally_len(options);
+        if bytes.len() != expected {
+            Error::EncryptedTallyLength {
+                actual: bytes.len(),
+                expected,
+            }
+        } else {
+            Error::EncryptedTallyRead
         }
+    })
+}
+
+/// Size of the encrypted tally of a proposal with `options` options, as
+/// serialized by chain_vote. It does not depend on the election key.
+fn encrypted_tally_len(options: usize) -> usize {
+    let election_key = ElectionPublicKey::from_participants(&[]);
+    let crs = Crs::from_hash(&[]);
+    EncryptedTally::new(options, election_key, crs)
+        .to_bytes()
+        .len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypted_tally_size_mismatch_is_reported() {
+        let election_key = ElectionPublicKey::from_participants(&[]);
+        let bytes = EncryptedTally::new(3, election_key, Crs::from_hash(&[])).to_bytes();
+        assert!(decode_encrypted_tally(&bytes, 3).is_ok());
+
+        let truncated = &bytes[..bytes.len() - 1];
+        assert!(matches!(
+            decode_encrypted_tally(truncated, 3),
+            Err(Error::EncryptedTallyLength { actual, expected })
+                if actual == bytes.len() - 1 && expected == bytes.len()
+        ));
     }
 }
@@ -0,0 +1,151 @@
+use super::decrypt_tally::{committee_shares, decrypt_vote_plan};
+use super::decryption_shares::merge_committee_shares;
+use super::Error;
+use crate::jcli_lib::utils::{vote, OutputFormat};
+use chain_crypto::bech32::Bech32;
+use chain_vote::MemberPublicKey;
+use jormungandr_lib::{
+    crypto::hash::Hash,
+    interfaces::{PrivateTallyState, Tally},
+};
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+/// Produce new tallies of a vote plan encrypted to a replacement committee,
+/// for committee member loss recovery drills.
+///
+/// The tallies are decrypted with the shares of the current committee and
+/// their results encrypted again to the election key of the new committee,
+/// so whoever runs the command learns the results. The ballots are not
+/// re-encrypted: each new tally only holds one ballot per option, weighted
+/// by its result.
+///
+/// The vote plan is printed with the new committee member keys and the new
+/// encrypted tallies. It keeps the id of the original vote plan but is not
+/// its on-chain state, which still holds the original tallies: the new
+/// tallies can only be decrypted off chain, with `decryption-shares` and
+/// `decrypt` run by the new committee on the printed vote plan.
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct TallyReencrypt {
+    /// The path to json-encoded vote plan to re-encrypt. If this parameter is
+    /// not specified, the vote plan will be read from the standard
+    /// input.
+    #[structopt(long)]
+    vote_plan: Option<PathBuf>,
+    /// The id of the vote plan to re-encrypt.
+    /// Can be left unspecified if there is only one vote plan in the input
+    #[structopt(long)]
+    vote_plan_id: Option<Hash>,
+    /// The paths to the decryption shares of each member of the current
+    /// committee, as produced by `decryption-shares`. Every member of the
+    /// current committee has to provide its shares.
+    #[structopt(long = "share", required = true)]
+    shares: Vec<PathBuf>,
+    /// The bech32 public key of a member of the new committee, repeated for
+    /// every member in committee order
+    #[structopt(
+        long = "new-member-key",
+        required = true,
+        parse(try_from_str = MemberPublicKey::try_from_bech32_str)
+    )]
+    new_member_keys: Vec<MemberPublicKey>,
+    /// Confirm new tallies only the new committee can decrypt are to be
+    /// produced. Nothing is done without it.
+    #[structopt(long)]
+    confirm_reencrypt: bool,
+    #[structopt(flatten)]
+    output_format: OutputFormat,
+}
+
+impl TallyReencrypt {
+    pub fn exec(&self) -> Result<(), Error> {
+        if !self.confirm_reencrypt {
+            return Err(Error::ReencryptNotConfirmed);
+        }
+        let vote_plan =
+            vote::get_vote_plan_by_id(self.vote_plan.as_ref(), self.vote_plan_id.as_ref())?;
+        check_new_committee(&vote_plan.committee_member_keys, &self.new_member_keys)?;
+
+        // decryption checks every tally is still encrypted
+        let total_stakes: Vec<_> = vote_plan
+            .proposals
+            .iter()
+            .map(|proposal| match &proposal.tally {
+                Tally::Private {
+                    state: PrivateTallyState::Encrypted { total_stake, .. },
+                } => Some(*total_stake),
+                _ => None,
+            })
+            .collect();
+        let shares = merge_committee_shares(&vote_plan, &self.shares)?;
+        let shares = committee_shares(&vote_plan, shares)?;
+        let mut vote_plan = decrypt_vote_plan(vote_plan, shares)?;
+
+        let election_key = chain_vote::ElectionPublicKey::from_participants(&self.new_member_keys);
+        let vote_plan_id = vote_plan.id.into_hash();
+        let crs = chain_vote::Crs::from_hash(vote_plan_id.as_ref());
+        let mut rng = rand::thread_rng();
+        for (proposal, total_stake) in vote_plan.proposals.iter_mut().zip(total_stakes) {
+            let results = match &proposal.tally {
+                Tally::Private {
+                    state: PrivateTallyState::Decrypted { result },
+                } => result.results(),
+                _ => unreachable!("expected decrypted private tally"),
+            };
+            let options = results.len();
+            let mut encrypted_tally =
+                chain_vote::EncryptedTally::new(options, election_key.clone(), crs.clone());
+            // adding a ballot for each option, weighted by its result, gives
+            // the same results once decrypted
+            for (option, weight) in results.into_iter().enumerate() {
+                if weight == 0 {
+                    continue;
+                }
+                let vote = chain_vote::Vote::new(options, option);
+                let (encrypted_vote, proof) =
+                    chain_vote::encrypt_vote(&mut rng, &crs, &election_key, vote);
+                let ballot = chain_vote::Ballot::try_from_vote_and_proof(
+                    encrypted_vote,
+                    &proof,
+                    &crs,
+                    &election_key,
+                )
+                .map_err(|_| Error::ReencryptedBallotInvalid {
+                    index: proposal.index,
+                })?;
+                encrypted_tally.add(&ballot, weight);
+            }
+            proposal.tally = Tally::Private {
+                state: PrivateTallyState::Encrypted {
+                    encrypted_tally: encrypted_tally.into(),
+                    total_stake: total_stake.expect("decrypted tallies were encrypted"),
+                },
+            };
+        }
+        vote_plan.committee_member_keys = self.new_member_keys.clone();
+
+        let output = self
+            .output_format
+            .format_json(serde_json::to_value(vote_plan)?)?;
+        println!("{}", output);
+        Ok(())
+    }
+}
+
+fn check_new_committee(current: &[MemberPublicKey], new: &[MemberPublicKey]) -> Result<(), Error> {
+    let new_keys: Vec<_> = new.iter().map(MemberPublicKey::to_bytes).collect();
+    for (index, key) in new_keys.iter().enumerate() {
+        if let Some(first) = new_keys[..index].iter().position(|other| other == key) {
+            return Err(Error::DuplicateNewCommitteeKey {
+                first,
+                second: index,
+            });
+        }
+    }
+    let current_keys: Vec<_> = current.iter().map(MemberPublicKey::to_bytes).collect();
+    if current_keys == new_keys {
+        return Err(Error::NewCommitteeUnchanged);
+    }
+    Ok(())
+}
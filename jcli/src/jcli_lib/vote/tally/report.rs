@@ -0,0 +1,128 @@
+use super::decrypt_tally::{committee_shares, decrypt_vote_plan};
+use super::decryption_shares::merge_committee_shares;
+use super::Error;
+use crate::jcli_lib::utils::{key_parser, vote, OutputFormat};
+use chain_crypto::{bech32::Bech32, hash::Blake2b256};
+use jormungandr_lib::{
+    crypto::hash::Hash,
+    interfaces::{PrivateTallyState, Tally},
+};
+use serde_json::{json, Value};
+use std::{collections::BTreeMap, path::PathBuf};
+use structopt::StructOpt;
+
+/// Decrypt the tallies of a vote plan and put the vote plan, its decrypted
+/// tallies, the hash of each encrypted tally and the decryption shares used
+/// in a single report, for archival.
+///
+/// The report carries a `content_hash`: the hexadecimal blake2b-256 hash of
+/// the compact JSON of the report without that field, with object keys in
+/// lexicographic order. Recomputing it detects a report damaged in storage,
+/// but anyone altering the report can recompute it as well: it says nothing
+/// about who produced the report.
+///
+/// With `--signing-key`, the same compact JSON is also signed and the
+/// report carries a `signature` with the public key of the signer and the
+/// bech32 signature, which `jcli key verify` checks.
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct TallyReport {
+    /// The path to json-encoded vote plan to decrypt. If this parameter is not
+    /// specified, the vote plan will be read from the standard
+    /// input.
+    #[structopt(long)]
+    vote_plan: Option<PathBuf>,
+    /// The id of the vote plan to decrypt.
+    /// Can be left unspecified if there is only one vote plan in the input
+    #[structopt(long)]
+    vote_plan_id: Option<Hash>,
+    /// The paths to the decryption shares of each committee member, as
+    /// produced by `decryption-shares`. Every member of the committee of the
+    /// vote plan has to provide its shares.
+    #[structopt(long = "share", required = true)]
+    shares: Vec<PathBuf>,
+    /// The path to an ed25519 secret key, in bech32, to sign the report with
+    #[structopt(long)]
+    signing_key: Option<PathBuf>,
+    #[structopt(flatten)]
+    output_format: OutputFormat,
+}
+
+impl TallyReport {
+    pub fn exec(&self) -> Result<(), Error> {
+        let vote_plan =
+            vote::get_vote_plan_by_id(self.vote_plan.as_ref(), self.vote_plan_id.as_ref())?;
+
+        let encrypted_tallies = vote_plan
+            .proposals
+            .iter()
+            .map(|proposal| match &proposal.tally {
+                Tally::Private {
+                    state:
+                        PrivateTallyState::Encrypted {
+                            encrypted_tally, ..
+                        },
+                } => Ok(json!({
+                    "index": proposal.index,
+                    "proposal_id": proposal.proposal_id.to_string(),
+                    "hash": content_hash(encrypted_tally.as_ref()),
+                })),
+                Tally::Public { .. } => Err(Error::PrivateTallyExpected {
+                    found: "public tally",
+                }),
+                Tally::Private { .. } => Err(Error::PrivateTallyExpected {
+                    found: "private decrypted tally",
+                }),
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let merged_shares = merge_committee_shares(&vote_plan, &self.shares)?;
+        let shares_used = serde_json::to_value(&merged_shares)?;
+        let shares = committee_shares(&vote_plan, merged_shares)?;
+        let vote_plan = decrypt_vote_plan(vote_plan, shares)?;
+
+        let mut report = sort_keys(json!({
+            "vote_plan": vote_plan,
+            "encrypted_tallies": encrypted_tallies,
+            "shares": shares_used,
+        }));
+        let content = serde_json::to_string(&report)?;
+        let signature = match &self.signing_key {
+            Some(path) => {
+                let key = key_parser::read_ed25519_secret_key_from_file(&Some(path))?;
+                Some(json!({
+                    "public_key": key.to_public().to_bech32_str(),
+                    "signature": key.sign(&content).to_bech32_str(),
+                }))
+            }
+            None => None,
+        };
+        report["content_hash"] = Value::String(content_hash(content.as_bytes()));
+        if let Some(signature) = signature {
+            report["signature"] = signature;
+        }
+
+        println!("{}", self.output_format.format_json(report)?);
+        Ok(())
+    }
+}
+
+fn content_hash(bytes: &[u8]) -> String {
+    Hash::from(Blake2b256::new(bytes)).to_hex()
+}
+
+// serde_json only keeps object keys sorted as long as its `preserve_order`
+// feature is off, which any crate of the build may turn on
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(object) => {
+            let sorted: BTreeMap<_, _> = object
+                .into_iter()
+                .map(|(key, value)| (key, sort_keys(value)))
+                .collect();
+            Value::Object(sorted.into_iter().collect())
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(sort_keys).collect()),
+        other => other,
+    }
+}
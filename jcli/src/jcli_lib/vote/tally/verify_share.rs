@@ -0,0 +1,101 @@
+use super::{decode_encrypted_tally, Error};
+use crate::jcli_lib::utils::{
+    io,
+    vote::{self, MemberVotePlanShares, SharesError, TallyDecryptShare},
+    OutputFormat,
+};
+use chain_crypto::bech32::Bech32;
+use chain_vote::committee::MemberPublicKey;
+use jormungandr_lib::{
+    crypto::hash::Hash,
+    interfaces::{PrivateTallyState, Tally},
+};
+use serde_json::json;
+use std::convert::TryFrom;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+/// Check that the decryption shares of a committee member are well-formed
+/// and match the encrypted tallies of a vote plan and the member public key,
+/// before they are combined with the shares of the other members.
+#[derive(StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+pub struct VerifyShare {
+    /// The path to json-encoded vote plan. If this parameter is not
+    /// specified, the vote plan will be read from standard input.
+    #[structopt(long)]
+    vote_plan: Option<PathBuf>,
+    /// The id of the vote plan.
+    /// Can be left unspecified if there is only one vote plan in the input
+    #[structopt(long)]
+    vote_plan_id: Option<Hash>,
+    /// The path to the decryption shares of the member, as produced by
+    /// `decryption-shares`
+    #[structopt(long)]
+    share: PathBuf,
+    /// The bech32 public key of the committee member who produced the shares
+    #[structopt(
+        long,
+        parse(try_from_str = MemberPublicKey::try_from_bech32_str)
+    )]
+    member_key: MemberPublicKey,
+    #[structopt(flatten)]
+    output_format: OutputFormat,
+}
+
+impl VerifyShare {
+    pub fn exec(&self) -> Result<(), Error> {
+        let vote_plan =
+            vote::get_vote_plan_by_id(self.vote_plan.as_ref(), self.vote_plan_id.as_ref())?;
+        let member_shares: MemberVotePlanShares =
+            serde_json::from_reader(io::open_file_read(&Some(&self.share))?)?;
+        let shares = Vec::<TallyDecryptShare>::from(member_shares);
+
+        // shares are produced for the encrypted tallies only, in order
+        let encrypted_tallies = vote_plan
+            .proposals
+            .iter()
+            .filter_map(|proposal| match &proposal.tally {
+                Tally::Private {
+                    state:
+                        PrivateTallyState::Encrypted {
+                            encrypted_tally, ..
+                        },
+                } => Some((proposal, encrypted_tally)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if encrypted_tallies.len() != shares.len() {
+            return Err(SharesError::ProposalSharesNotBalanced.into());
+        }
+
+        let member_key = [self.member_key.clone()];
+        let proposals = encrypted_tallies
+            .into_iter()
+            .zip(shares)
+            .map(|((proposal, encrypted_tally), share)| {
+                let encrypted_tally =
+                    decode_encrypted_tally(encrypted_tally.as_ref(), proposal.options.len())?;
+                let verified = chain_vote::TallyDecryptShare::try_from(share).and_then(|share| {
+                    encrypted_tally
+                        .validate_partial_decryptions(&member_key, &[share])
+                        .map(|_| ())
+                        .map_err(SharesError::ValidationFailed)
+                });
+                Ok(match verified {
+                    Ok(()) => json!({ "index": proposal.index, "valid": true }),
+                    Err(error) => json!({
+                        "index": proposal.index,
+                        "valid": false,
+                        "error": error.to_string(),
+                    }),
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let valid = proposals.iter().all(|proposal| proposal["valid"] == true);
+        let report = json!({ "valid": valid, "proposals": proposals });
+        println!("{}", self.output_format.format_json(report)?);
+        Ok(())
+    }
+}
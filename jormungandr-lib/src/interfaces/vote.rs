@@ -551,6 +551,12 @@ pub mod serde_base64_bytes {
     }
 }
 
+impl From<chain_vote::EncryptedTally> for EncryptedTally {
+    fn from(encrypted_tally: chain_vote::EncryptedTally) -> EncryptedTally {
+        EncryptedTally(encrypted_tally.to_bytes())
+    }
+}
+
 impl From<EncryptedTally> for chain_vote::EncryptedTally {
     fn from(encrypted_tally: EncryptedTally) -> chain_vote::EncryptedTally {
         chain_vote::EncryptedTally::from_bytes(&encrypted_tally.0).unwrap()
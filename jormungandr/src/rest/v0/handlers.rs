@@ -258,3 +258,15 @@ pub async fn get_active_vote_plans(context: ContextLock) -> Result<impl Reply, R
         .map(|r| warp::reply::json(&r))
         .map_err(warp::reject::custom)
 }
+
+pub async fn get_active_vote_plan(
+    id_hex: String,
+    context: ContextLock,
+) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::get_active_vote_plan(&context, &id_hex)
+        .await
+        .map_err(warp::reject::custom)?
+        .map(|r| warp::reply::json(&r))
+        .ok_or_else(warp::reject::not_found)
+}
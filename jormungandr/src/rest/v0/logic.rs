@@ -587,3 +587,14 @@ pub async fn get_active_vote_plans(context: &Context) -> Result<Vec<VotePlanStat
         .collect();
     Ok(vp)
 }
+
+pub async fn get_active_vote_plan(
+    context: &Context,
+    id_hex: &str,
+) -> Result<Option<VotePlanStatus>, Error> {
+    let id: jormungandr_lib::crypto::hash::Hash = id_hex.parse()?;
+    Ok(get_active_vote_plans(context)
+        .await?
+        .into_iter()
+        .find(|vote_plan| vote_plan.id == id))
+}
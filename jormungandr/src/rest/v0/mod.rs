@@ -222,10 +222,16 @@ pub fn filter(
 
         let vote_plans = warp::path!("plans")
             .and(warp::get())
-            .and(with_context)
+            .and(with_context.clone())
             .and_then(handlers::get_active_vote_plans)
             .boxed();
-        root.and(committees.or(vote_plans)).boxed()
+
+        let vote_plan = warp::path!("plans" / String)
+            .and(warp::get())
+            .and(with_context)
+            .and_then(handlers::get_active_vote_plan)
+            .boxed();
+        root.and(committees.or(vote_plans).or(vote_plan)).boxed()
     };
 
     let routes = shutdown
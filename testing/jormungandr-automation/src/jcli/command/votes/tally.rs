@@ -21,7 +21,9 @@ impl TallyCommand {
             .arg("--vote-plan-id")
             .arg(vote_plan_id.into())
             .arg("--key")
-            .arg(member_key.as_ref());
+            .arg(member_key.as_ref())
+            .arg("--output-format")
+            .arg("json-compact");
         self
     }
 
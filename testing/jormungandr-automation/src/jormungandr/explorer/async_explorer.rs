@@ -0,0 +1,247 @@
+use super::{
+    builder::{BOOTSTRAP_PROBE_INTERVAL, BOOTSTRAP_STDERR_TAIL_LINES, DEFAULT_BOOTSTRAP_TIMEOUT},
+    client::AsyncGraphQlClient,
+    data::{
+        address, all_blocks, all_stake_pools, all_vote_plans, blocks_by_chain_length, epoch,
+        last_block, settings, stake_pool, transaction_by_id, Address, AllBlocks, AllStakePools,
+        AllVotePlans, BlocksByChainLength, Epoch, LastBlock, PoolId, Settings, StakePool,
+        TransactionById,
+    },
+    decode_body, log,
+    process::{ExplorerProcess, DEFAULT_LOG_BUFFER_LINES, DEFAULT_SHUTDOWN_GRACE_PERIOD},
+    ExplorerError, LastBlockResponse,
+};
+use crate::jormungandr::get_available_port;
+use graphql_client::{GraphQLQuery, QueryBody, Response};
+use jormungandr_lib::{crypto::hash::Hash, interfaces::BlockDate};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    fmt::Debug,
+    net::{Ipv4Addr, SocketAddr},
+    path::PathBuf,
+    sync::Arc,
+};
+
+/// Non-blocking counterpart of [`Explorer`](super::Explorer).
+///
+/// Exposes the same queries as `async fn`s backed by `reqwest::Client`, so
+/// it can be driven from a tokio runtime without dedicating a thread to
+/// each explorer instance.
+#[derive(Clone)]
+pub struct AsyncExplorer {
+    client: AsyncGraphQlClient,
+    listen_addr: SocketAddr,
+    print_log: bool,
+    _process: Arc<ExplorerProcess>,
+}
+
+impl AsyncExplorer {
+    /// Spawns the explorer and waits for it to accept connections, failing
+    /// like [`Explorer::try_new`](super::Explorer::try_new) if it cannot be
+    /// started or does not answer within the bootstrap timeout.
+    pub async fn new(
+        node_address: String,
+        logs_dir: Option<PathBuf>,
+    ) -> Result<AsyncExplorer, ExplorerError> {
+        let print_log = true;
+
+        let explorer_port = get_available_port();
+        let listen_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, explorer_port));
+        let explorer_listen_address = listen_addr.to_string();
+
+        let _process = Arc::new(ExplorerProcess::spawn(
+            &node_address,
+            &explorer_listen_address,
+            logs_dir,
+            DEFAULT_SHUTDOWN_GRACE_PERIOD,
+            DEFAULT_LOG_BUFFER_LINES,
+            &[],
+        )?);
+
+        let probe = reqwest::Client::new();
+        let attempts =
+            (DEFAULT_BOOTSTRAP_TIMEOUT.as_millis() / BOOTSTRAP_PROBE_INTERVAL.as_millis()).max(1);
+        let mut attempt = 0;
+        loop {
+            if probe
+                .head(format!("http://{}/", &explorer_listen_address))
+                .send()
+                .await
+                .is_ok()
+            {
+                break;
+            };
+
+            if let Some((status, stderr)) = _process.exited(BOOTSTRAP_STDERR_TAIL_LINES) {
+                return Err(ExplorerError::ProcessExited { status, stderr });
+            }
+
+            attempt += 1;
+            if attempt >= attempts {
+                return Err(ExplorerError::BootstrapTimeout {
+                    address: explorer_listen_address,
+                    timeout: DEFAULT_BOOTSTRAP_TIMEOUT,
+                });
+            }
+            tokio::time::sleep(BOOTSTRAP_PROBE_INTERVAL).await;
+        }
+
+        Ok(AsyncExplorer {
+            client: AsyncGraphQlClient::new(explorer_listen_address),
+            listen_addr,
+            print_log,
+            _process,
+        })
+    }
+
+    pub fn uri(&self) -> String {
+        self.client.base_url()
+    }
+
+    pub fn listen_addr(&self) -> SocketAddr {
+        self.listen_addr
+    }
+
+    pub fn port(&self) -> u16 {
+        self.listen_addr.port()
+    }
+
+    pub fn disable_logs(&mut self) {
+        self.print_log = false;
+        self.client.disable_print();
+    }
+
+    pub fn enable_logs(&mut self) {
+        self.print_log = true;
+        self.client.enable_print();
+    }
+
+    pub fn print_request<T: Serialize>(&self, query: &QueryBody<T>) {
+        if !self.print_log {
+            return;
+        }
+
+        log::query(query.operation_name, query.query, None, &self.uri());
+    }
+
+    pub async fn address<S: Into<String>>(
+        &self,
+        bech32_address: S,
+    ) -> Result<Response<address::ResponseData>, ExplorerError> {
+        self.query::<Address>(address::Variables {
+            bech32: bech32_address.into(),
+        })
+        .await
+    }
+
+    pub async fn stake_pools(
+        &self,
+        limit: i64,
+    ) -> Result<Response<all_stake_pools::ResponseData>, ExplorerError> {
+        self.query::<AllStakePools>(all_stake_pools::Variables { first: limit })
+            .await
+    }
+
+    pub async fn blocks(
+        &self,
+        limit: i64,
+    ) -> Result<Response<all_blocks::ResponseData>, ExplorerError> {
+        self.query::<AllBlocks>(all_blocks::Variables { last: limit })
+            .await
+    }
+
+    pub async fn last_block(&self) -> Result<LastBlockResponse, ExplorerError> {
+        self.query::<LastBlock>(last_block::Variables)
+            .await
+            .map(LastBlockResponse::new)
+    }
+
+    pub async fn blocks_at_chain_length(
+        &self,
+        length: u32,
+    ) -> Result<Response<blocks_by_chain_length::ResponseData>, ExplorerError> {
+        self.query::<BlocksByChainLength>(blocks_by_chain_length::Variables {
+            length: length.to_string(),
+        })
+        .await
+    }
+
+    pub async fn epoch(
+        &self,
+        epoch_number: u32,
+        limit: i64,
+    ) -> Result<Response<epoch::ResponseData>, ExplorerError> {
+        self.query::<Epoch>(epoch::Variables {
+            id: epoch_number.to_string(),
+            blocks_limit: limit,
+        })
+        .await
+    }
+
+    pub async fn stake_pool(
+        &self,
+        id: PoolId,
+        limit: i64,
+    ) -> Result<Response<stake_pool::ResponseData>, ExplorerError> {
+        self.query::<StakePool>(stake_pool::Variables { id, first: limit })
+            .await
+    }
+
+    pub async fn settings(&self) -> Result<Response<settings::ResponseData>, ExplorerError> {
+        self.query::<Settings>(settings::Variables).await
+    }
+
+    pub async fn vote_plans(
+        &self,
+        limit: i64,
+    ) -> Result<Response<all_vote_plans::ResponseData>, ExplorerError> {
+        self.query::<AllVotePlans>(all_vote_plans::Variables { first: limit })
+            .await
+    }
+
+    pub async fn transaction(
+        &self,
+        hash: Hash,
+    ) -> Result<Response<transaction_by_id::ResponseData>, ExplorerError> {
+        self.query::<TransactionById>(transaction_by_id::Variables {
+            id: hash.to_string(),
+        })
+        .await
+    }
+
+    pub async fn current_time(&self) -> BlockDate {
+        self.last_block().await.unwrap().block_date()
+    }
+
+    pub async fn run<T: Serialize>(
+        &self,
+        query: QueryBody<T>,
+    ) -> Result<reqwest::Response, ExplorerError> {
+        self.print_request(&query);
+        let response = self.client.run(query).await.map_err(ExplorerError::from)?;
+        self.print_log(&response);
+        Ok(response)
+    }
+
+    async fn query<Q>(
+        &self,
+        variables: Q::Variables,
+    ) -> Result<Response<Q::ResponseData>, ExplorerError>
+    where
+        Q: GraphQLQuery,
+        Q::ResponseData: DeserializeOwned + Debug,
+    {
+        let query = Q::build_query(variables);
+        self.print_request(&query);
+        let response = self.client.run(query).await.map_err(ExplorerError::from)?;
+        let response_body: Response<Q::ResponseData> = decode_body(&response.text().await?)?;
+        self.print_log(&response_body);
+        Ok(response_body)
+    }
+
+    fn print_log<T: Debug>(&self, response: &T) {
+        if self.print_log {
+            log::response(response);
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use super::{
+    client::GraphQlClient,
+    log::DEFAULT_MAX_LOGGED_VARIABLE_LEN,
+    process::{
+        ExplorerProcess, DEFAULT_LOG_BUFFER_LINES, DEFAULT_SHUTDOWN_GRACE_PERIOD, RESERVED_ARGS,
+    },
+    Explorer, ExplorerError,
+};
+use crate::jormungandr::get_available_port;
+use jortestkit::process::Wait;
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
+
+pub(super) const DEFAULT_BOOTSTRAP_TIMEOUT: Duration = Duration::from_secs(10);
+pub(super) const BOOTSTRAP_PROBE_INTERVAL: Duration = Duration::from_secs(1);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Lines of stderr put in [`ExplorerError::ProcessExited`].
+pub(super) const BOOTSTRAP_STDERR_TAIL_LINES: usize = 20;
+pub const DEFAULT_POOL_MAX_IDLE_PER_HOST: usize = 32;
+pub const DEFAULT_POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+/// Levels understood by the explorer `--log-level` flag.
+pub(super) const LOG_LEVELS: &[&str] = &["off", "error", "warn", "info", "debug", "trace"];
+
+pub struct ExplorerBuilder {
+    node_address: String,
+    logs_dir: Option<PathBuf>,
+    bootstrap_timeout: Duration,
+    shutdown_grace_period: Duration,
+    extra_args: Vec<String>,
+    pool_max_idle_per_host: usize,
+    pool_idle_timeout: Option<Duration>,
+    log_level: Option<String>,
+    log_buffer_lines: usize,
+}
+
+impl ExplorerBuilder {
+    pub fn new<S: Into<String>>(node_address: S) -> Self {
+        Self {
+            node_address: node_address.into(),
+            logs_dir: None,
+            bootstrap_timeout: DEFAULT_BOOTSTRAP_TIMEOUT,
+            shutdown_grace_period: DEFAULT_SHUTDOWN_GRACE_PERIOD,
+            extra_args: Vec::new(),
+            pool_max_idle_per_host: DEFAULT_POOL_MAX_IDLE_PER_HOST,
+            pool_idle_timeout: Some(DEFAULT_POOL_IDLE_TIMEOUT),
+            log_level: None,
+            log_buffer_lines: DEFAULT_LOG_BUFFER_LINES,
+        }
+    }
+
+    /// Builder for the node REST API listening on `address`, which can not
+    /// be rejected as [`ExplorerError::InvalidNodeAddress`].
+    pub fn from_socket_addr(address: SocketAddr) -> Self {
+        Self::new(format!("http://{}/", address))
+    }
+
+    pub fn logs_dir(&mut self, logs_dir: Option<PathBuf>) -> &mut Self {
+        self.logs_dir = logs_dir;
+        self
+    }
+
+    pub fn bootstrap_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.bootstrap_timeout = timeout;
+        self
+    }
+
+    /// How long the explorer is given to exit after SIGTERM before it is
+    /// killed. Ignored on platforms without signals, where the process is
+    /// killed straight away.
+    pub fn shutdown_grace_period(&mut self, grace_period: Duration) -> &mut Self {
+        self.shutdown_grace_period = grace_period;
+        self
+    }
+
+    /// Appends `args` to the explorer command line, e.g. to pass a config
+    /// file to a patched explorer. The flags the builder sets itself
+    /// (`--node`, `--binding-address`, `--log-output`, `--log-level`) are
+    /// rejected by
+    /// [`build`](Self::build).
+    pub fn extra_args(&mut self, args: Vec<String>) -> &mut Self {
+        self.extra_args.extend(args);
+        self
+    }
+
+    /// Maximum number of idle connections kept open to the explorer, shared
+    /// by every clone of the built [`Explorer`]. Concurrent queries beyond
+    /// that open extra connections which are closed once done, so a value
+    /// close to the expected concurrency avoids churning through ephemeral
+    /// ports. `0` disables pooling.
+    pub fn pool_max_idle_per_host(&mut self, max_idle: usize) -> &mut Self {
+        self.pool_max_idle_per_host = max_idle;
+        self
+    }
+
+    /// How long an idle pooled connection is kept before being closed, or
+    /// `None` to keep it until the explorer closes it. Reused connections
+    /// skip the connect timeout, which only bounds opening new ones, and the
+    /// compression setting is sent with every request so it is unaffected
+    /// by pooling.
+    pub fn pool_idle_timeout(&mut self, timeout: Option<Duration>) -> &mut Self {
+        self.pool_idle_timeout = timeout;
+        self
+    }
+
+    /// Verbosity of the explorer logs, one of `off`, `error`, `warn`, `info`,
+    /// `debug` or `trace`. Unknown levels are rejected by
+    /// [`build`](Self::build) with [`ExplorerError::InvalidLogLevel`]. When
+    /// not set the explorer keeps its own default.
+    pub fn log_level(&mut self, level: &str) -> &mut Self {
+        self.log_level = Some(level.to_string());
+        self
+    }
+
+    /// Number of output lines kept in memory for
+    /// [`Explorer::recent_logs`] and the dump made when a test panics. The
+    /// output is drained as it is produced whatever the value, older lines
+    /// are dropped first.
+    pub fn log_buffer_lines(&mut self, lines: usize) -> &mut Self {
+        self.log_buffer_lines = lines;
+        self
+    }
+
+    /// Spawns the explorer and waits up to the bootstrap timeout for it to
+    /// accept connections, failing with [`ExplorerError::BootstrapTimeout`]
+    /// instead of handing back a client pointed at a port nobody listens on.
+    /// Fails early with [`ExplorerError::ProcessExited`] if the explorer
+    /// exits before that.
+    pub fn build(&self) -> Result<Explorer, ExplorerError> {
+        validate_node_address(&self.node_address)?;
+        if let Some(arg) = self.extra_args.iter().find(|arg| is_reserved(arg)) {
+            return Err(ExplorerError::ReservedArgument(arg.clone()));
+        }
+
+        let mut args = Vec::new();
+        if let Some(level) = &self.log_level {
+            let level = level.to_lowercase();
+            if !LOG_LEVELS.contains(&level.as_str()) {
+                return Err(ExplorerError::InvalidLogLevel(level));
+            }
+            args.push("--log-level".to_string());
+            args.push(level);
+        }
+        args.extend_from_slice(&self.extra_args);
+
+        let explorer_port = get_available_port();
+        let listen_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, explorer_port));
+        let explorer_listen_address = listen_addr.to_string();
+
+        let process = Arc::new(ExplorerProcess::spawn(
+            &self.node_address,
+            &explorer_listen_address,
+            self.logs_dir.clone(),
+            self.shutdown_grace_period,
+            self.log_buffer_lines,
+            &args,
+        )?);
+
+        let client = reqwest::blocking::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .build()?;
+
+        let client = GraphQlClient::with_client(explorer_listen_address.clone(), client);
+
+        let attempts =
+            (self.bootstrap_timeout.as_millis() / BOOTSTRAP_PROBE_INTERVAL.as_millis()).max(1);
+        let mut wait_bootstrap = Wait::new(BOOTSTRAP_PROBE_INTERVAL, attempts as _);
+        loop {
+            if client.http_client().head(client.root_url()).send().is_ok() {
+                break;
+            };
+
+            if let Some((status, stderr)) = process.exited(BOOTSTRAP_STDERR_TAIL_LINES) {
+                return Err(ExplorerError::ProcessExited { status, stderr });
+            }
+
+            if wait_bootstrap.timeout_reached() {
+                return Err(ExplorerError::BootstrapTimeout {
+                    address: explorer_listen_address,
+                    timeout: self.bootstrap_timeout,
+                });
+            }
+            wait_bootstrap.advance();
+        }
+
+        Ok(Explorer {
+            client,
+            listen_addr,
+            print_log: true,
+            redact_variables: true,
+            max_logged_variable_len: DEFAULT_MAX_LOGGED_VARIABLE_LEN,
+            process: Some(process),
+            fixtures: None,
+            transport: None,
+        })
+    }
+}
+
+/// The explorer expects the URL of the node, e.g. `http://127.0.0.1:8080/`,
+/// and would otherwise only fail once it tries to connect to it.
+fn validate_node_address(address: &str) -> Result<(), ExplorerError> {
+    match reqwest::Url::parse(address) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") && url.host().is_some() => Ok(()),
+        _ => Err(ExplorerError::InvalidNodeAddress(address.to_string())),
+    }
+}
+
+fn is_reserved(arg: &str) -> bool {
+    let flag = arg.split('=').next().unwrap_or(arg);
+    RESERVED_ARGS.contains(&flag)
+}
@@ -1,26 +1,83 @@
+use super::{
+    log,
+    metrics::{MetricsRecorder, QueryMetrics},
+};
 use graphql_client::QueryBody;
 use serde::Serialize;
 use std::fmt::Debug;
+use std::io::Read;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
+/// Largest response body read by default, after decompression.
+pub const DEFAULT_MAX_RESPONSE_SIZE: u64 = 256 * 1024 * 1024;
+/// Pause between two attempts at a query which could not reach the explorer.
+const RETRY_INTERVAL: Duration = Duration::from_millis(200);
+/// Name logged for queries sent with [`GraphQlClient::run_raw`].
+pub(super) const RAW_OPERATION_NAME: &str = "raw query";
+
 #[derive(Clone)]
 pub struct GraphQlClient {
     base_url: String,
+    root_url: String,
     print_out: bool,
+    compression: bool,
+    max_response_size: u64,
+    /// `None` unless metrics collection was turned on.
+    metrics: Option<MetricsRecorder>,
+    query_deadline: Option<Duration>,
+    client: reqwest::blocking::Client,
 }
 
 #[derive(Error, Debug)]
 pub enum GraphQlClientError {
     #[error("request error")]
     ReqwestError(#[from] reqwest::Error),
+    #[error("json serialization error")]
+    SerializationError(#[from] serde_json::Error),
+    #[error("batch of {sent} operations was answered with {received} responses")]
+    BatchMismatch { sent: usize, received: usize },
+    #[error("response is larger than the {limit} bytes limit")]
+    ResponseTooLarge { limit: u64 },
+    #[error("could not read response body")]
+    BodyReadError(#[from] std::io::Error),
+    #[error("query did not complete within its {0:?} deadline")]
+    DeadlineExceeded(Duration),
+    #[error("no mock response for operation '{0}'")]
+    NoMockResponse(String),
 }
 
 impl GraphQlClient {
     pub fn new<S: Into<String>>(base_address: S) -> GraphQlClient {
-        let base_url = format!("http://{}/graphql", base_address.into());
+        Self::with_client(base_address, reqwest::blocking::Client::new())
+    }
+
+    /// Sends every query through `client`, so its connection pool is shared
+    /// with whoever else holds a clone of it.
+    pub fn with_client<S: Into<String>>(
+        base_address: S,
+        client: reqwest::blocking::Client,
+    ) -> GraphQlClient {
+        Self::with_scheme("http", base_address, client)
+    }
+
+    /// Like [`GraphQlClient::with_client`], but reaching the explorer with
+    /// `scheme`, e.g. `https` for an explorer behind TLS.
+    pub fn with_scheme<S: Into<String>>(
+        scheme: &str,
+        base_address: S,
+        client: reqwest::blocking::Client,
+    ) -> GraphQlClient {
+        let root_url = format!("{}://{}/", scheme, base_address.into());
         GraphQlClient {
-            base_url,
+            base_url: format!("{}graphql", root_url),
+            root_url,
             print_out: true,
+            compression: true,
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            metrics: None,
+            query_deadline: None,
+            client,
         }
     }
 
@@ -28,6 +85,21 @@ impl GraphQlClient {
         self.base_url.to_string()
     }
 
+    /// Root of the explorer server, with the same scheme as the GraphQL
+    /// endpoint.
+    pub fn root_url(&self) -> &str {
+        &self.root_url
+    }
+
+    /// WebSocket endpoint serving the GraphQL subscriptions.
+    pub fn subscription_url(&self) -> String {
+        format!("{}subscription", self.root_url.replacen("http", "ws", 1))
+    }
+
+    pub(super) fn http_client(&self) -> &reqwest::blocking::Client {
+        &self.client
+    }
+
     pub fn enable_print(&mut self) {
         self.print_out = true;
     }
@@ -36,20 +108,287 @@ impl GraphQlClient {
         self.print_out = false;
     }
 
+    /// Responses are requested gzip-compressed and decompressed
+    /// transparently. An explorer which does not compress still answers in
+    /// plain JSON, which is read as is.
+    pub fn enable_compression(&mut self) {
+        self.compression = true;
+    }
+
+    /// Asks the explorer for uncompressed responses, e.g. to inspect the raw
+    /// payloads on the wire.
+    pub fn disable_compression(&mut self) {
+        self.compression = false;
+    }
+
+    /// Starts or stops recording the duration of every request. The samples
+    /// are shared with the clones made while collection is on, and dropped
+    /// when it is turned off.
+    pub fn collect_metrics(&mut self, collect: bool) {
+        match (collect, &self.metrics) {
+            (true, None) => self.metrics = Some(MetricsRecorder::default()),
+            (false, Some(_)) => self.metrics = None,
+            _ => {}
+        }
+    }
+
+    /// Latency statistics of the requests sent since metrics collection was
+    /// turned on, `None` when it is off or nothing was sent yet.
+    pub fn query_metrics(&self) -> Option<QueryMetrics> {
+        self.metrics.as_ref().and_then(MetricsRecorder::metrics)
+    }
+
+    /// Responses whose body is larger than `max_size` bytes are rejected
+    /// with [`GraphQlClientError::ResponseTooLarge`] by
+    /// [`GraphQlClient::read_body`], without reading the rest of the body.
+    pub fn set_max_response_size(&mut self, max_size: u64) {
+        self.max_response_size = max_size;
+    }
+
+    /// Bounds the time spent on each query, from connecting to the explorer
+    /// to reading the whole response body, whatever the timeout of the
+    /// underlying reqwest client. Queries failing to connect are attempted
+    /// again until `deadline`, each attempt being given the time left, and
+    /// queries still running past it fail with
+    /// [`GraphQlClientError::DeadlineExceeded`]. `None`, the default, leaves
+    /// queries to a single attempt bounded by the client timeout.
+    pub fn set_query_deadline(&mut self, deadline: Option<Duration>) {
+        self.query_deadline = deadline;
+    }
+
+    pub fn query_deadline(&self) -> Option<Duration> {
+        self.query_deadline
+    }
+
+    /// Reads the body of `response`, stopping as soon as it exceeds the
+    /// maximum response size.
+    pub fn read_body(
+        &self,
+        response: reqwest::blocking::Response,
+    ) -> Result<String, GraphQlClientError> {
+        let limit = self.max_response_size;
+        if response.content_length().map_or(false, |len| len > limit) {
+            return Err(GraphQlClientError::ResponseTooLarge { limit });
+        }
+        let mut body = Vec::new();
+        response
+            .take(limit.saturating_add(1))
+            .read_to_end(&mut body)
+            .map_err(|e| match self.query_deadline {
+                Some(deadline) if e.kind() == std::io::ErrorKind::TimedOut => {
+                    GraphQlClientError::DeadlineExceeded(deadline)
+                }
+                _ => e.into(),
+            })?;
+        if body.len() as u64 > limit {
+            return Err(GraphQlClientError::ResponseTooLarge { limit });
+        }
+        Ok(String::from_utf8_lossy(&body).into_owned())
+    }
+
+    fn post(&self, timeout: Option<Duration>) -> reqwest::blocking::RequestBuilder {
+        let mut request = self.client.post(&self.base_url);
+        if let Some(timeout) = timeout {
+            request = request.timeout(timeout);
+        }
+        if self.compression {
+            // reqwest adds `Accept-Encoding: gzip` and decodes the body
+            request
+        } else {
+            request.header(reqwest::header::ACCEPT_ENCODING, "identity")
+        }
+    }
+
+    fn send(
+        &self,
+        request: reqwest::blocking::RequestBuilder,
+    ) -> Result<reqwest::blocking::Response, GraphQlClientError> {
+        request.send().map_err(|e| match self.query_deadline {
+            Some(deadline) if e.is_timeout() => GraphQlClientError::DeadlineExceeded(deadline),
+            _ => e.into(),
+        })
+    }
+
+    /// Posts `body` and hands the response to `read`, attempting it again
+    /// after connection failures until the query deadline has passed.
+    fn attempt<B, R>(
+        &self,
+        body: &B,
+        read: impl Fn(reqwest::blocking::Response) -> Result<R, GraphQlClientError>,
+    ) -> Result<R, GraphQlClientError>
+    where
+        B: Serialize + ?Sized,
+    {
+        let limit = match self.query_deadline {
+            Some(limit) => limit,
+            None => return self.send(self.post(None).json(body)).and_then(read),
+        };
+        let deadline = Instant::now() + limit;
+        loop {
+            let remaining = deadline
+                .checked_duration_since(Instant::now())
+                .filter(|remaining| !remaining.is_zero())
+                .ok_or(GraphQlClientError::DeadlineExceeded(limit))?;
+            match self
+                .send(self.post(Some(remaining)).json(body))
+                .and_then(&read)
+            {
+                Err(GraphQlClientError::ReqwestError(error)) if error.is_connect() => {
+                    std::thread::sleep(RETRY_INTERVAL.min(remaining));
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Runs `query`, recording how long it took if metrics are collected.
+    /// `query` is expected to cover sending the request as well as reading
+    /// and decoding the response.
+    pub(super) fn timed<R, E>(&self, query: impl FnOnce() -> Result<R, E>) -> Result<R, E> {
+        let started = Instant::now();
+        let result = query();
+        if let Some(metrics) = &self.metrics {
+            metrics.record(started.elapsed());
+        }
+        result
+    }
+
+    /// Sends `query`, leaving the response to be read by the caller. As
+    /// the body is not read here, the query is not part of the metrics.
     pub fn run<T: Serialize>(
         &self,
         query: QueryBody<T>,
     ) -> Result<reqwest::blocking::Response, GraphQlClientError> {
         if self.print_out {
-            println!(
-                "running query: {:#?}, against: {}",
-                query.query, self.base_url
-            );
+            log::query(query.operation_name, query.query, None, &self.base_url);
+        }
+        self.attempt(&query, Ok)
+    }
+
+    /// Sends `query` and returns the body of its response.
+    pub fn run_body<T: Serialize>(
+        &self,
+        query: QueryBody<T>,
+    ) -> Result<String, GraphQlClientError> {
+        if self.print_out {
+            log::query(query.operation_name, query.query, None, &self.base_url);
+        }
+        self.attempt(&query, |response| self.read_body(response))
+    }
+
+    /// Sends a query which has no generated bindings, returning the whole
+    /// response body, `errors` included.
+    pub fn run_raw(
+        &self,
+        query: &str,
+        variables: &serde_json::Value,
+    ) -> Result<serde_json::Value, GraphQlClientError> {
+        if self.print_out {
+            log::query(RAW_OPERATION_NAME, query, None, &self.base_url);
+        }
+        let body = serde_json::json!({ "query": query, "variables": variables });
+        self.timed(|| {
+            self.attempt(&body, |response| {
+                Ok(serde_json::from_str(&self.read_body(response)?)?)
+            })
+        })
+    }
+
+    /// Sends all `queries` in a single request. The responses are returned
+    /// in the order of the queries, each with its own `errors`, so a failing
+    /// operation does not fail the others.
+    pub fn run_batch<T: Serialize>(
+        &self,
+        queries: &[QueryBody<T>],
+    ) -> Result<Vec<serde_json::Value>, GraphQlClientError> {
+        if self.print_out {
+            for query in queries {
+                log::query(query.operation_name, query.query, None, &self.base_url);
+            }
+        }
+        let responses: Vec<serde_json::Value> = self.timed(|| {
+            self.attempt(queries, |response| {
+                Ok(serde_json::from_str(&self.read_body(response)?)?)
+            })
+        })?;
+        if responses.len() != queries.len() {
+            return Err(GraphQlClientError::BatchMismatch {
+                sent: queries.len(),
+                received: responses.len(),
+            });
+        }
+        Ok(responses)
+    }
+}
+
+#[derive(Clone)]
+pub struct AsyncGraphQlClient {
+    base_url: String,
+    print_out: bool,
+    client: reqwest::Client,
+}
+
+impl AsyncGraphQlClient {
+    pub fn new<S: Into<String>>(base_address: S) -> AsyncGraphQlClient {
+        let base_url = format!("http://{}/graphql", base_address.into());
+        AsyncGraphQlClient {
+            base_url,
+            print_out: true,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    pub fn base_url(&self) -> String {
+        self.base_url.to_string()
+    }
+
+    pub fn enable_print(&mut self) {
+        self.print_out = true;
+    }
+
+    pub fn disable_print(&mut self) {
+        self.print_out = false;
+    }
+
+    pub async fn run<T: Serialize>(
+        &self,
+        query: QueryBody<T>,
+    ) -> Result<reqwest::Response, GraphQlClientError> {
+        if self.print_out {
+            log::query(query.operation_name, query.query, None, &self.base_url);
         }
-        reqwest::blocking::Client::new()
+        self.client
             .post(&self.base_url)
             .json(&query)
             .send()
+            .await
             .map_err(|e| e.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn connection_failures_are_retried_until_the_deadline() {
+        // nothing listens on the port once the listener is dropped
+        let address = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let mut client = GraphQlClient::new(address.to_string());
+        client.disable_print();
+        let deadline = Duration::from_millis(500);
+        client.set_query_deadline(Some(deadline));
+
+        let started = Instant::now();
+        let result = client.run_raw("{ version }", &serde_json::Value::Null);
+        assert!(matches!(
+            result,
+            Err(GraphQlClientError::DeadlineExceeded(limit)) if limit == deadline
+        ));
+        assert!(started.elapsed() >= deadline);
+    }
+}
@@ -4,6 +4,10 @@ pub type ChainLength = String;
 pub type PoolId = String;
 pub type Value = String;
 pub type VotePlanId = String;
+pub type ExternalProposalId = String;
+pub type Weight = String;
+pub type TimeOffsetSeconds = String;
+pub type NonZero = u64;
 
 use graphql_client::GraphQLQuery;
 
@@ -23,6 +27,14 @@ pub struct Address;
 )]
 pub struct AllBlocks;
 
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "resources/explorer/graphql/allblocks_page.graphql",
+    schema_path = "resources/explorer/graphql/schema.graphql",
+    response_derives = "Debug"
+)]
+pub struct AllBlocksPage;
+
 #[derive(GraphQLQuery)]
 #[graphql(
     query_path = "resources/explorer/graphql/allstakepools.graphql",
@@ -31,6 +43,14 @@ pub struct AllBlocks;
 )]
 pub struct AllStakePools;
 
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "resources/explorer/graphql/allstakepools_page.graphql",
+    schema_path = "resources/explorer/graphql/schema.graphql",
+    response_derives = "Debug"
+)]
+pub struct AllStakePoolsPage;
+
 #[derive(GraphQLQuery)]
 #[graphql(
     query_path = "resources/explorer/graphql/blocksbychainlength.graphql",
@@ -79,6 +99,14 @@ pub struct Settings;
 )]
 pub struct TransactionById;
 
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "resources/explorer/graphql/transactions_by_address.graphql",
+    schema_path = "resources/explorer/graphql/schema.graphql",
+    response_derives = "Debug"
+)]
+pub struct TransactionsByAddress;
+
 #[derive(GraphQLQuery)]
 #[allow(clippy::upper_case_acronyms)]
 #[graphql(
@@ -87,3 +115,85 @@ pub struct TransactionById;
     response_derives = "Debug"
 )]
 pub struct AllVotePlans;
+
+#[derive(GraphQLQuery)]
+#[allow(clippy::upper_case_acronyms)]
+#[graphql(
+    query_path = "resources/explorer/graphql/vote_plan_by_id.graphql",
+    schema_path = "resources/explorer/graphql/schema.graphql",
+    response_derives = "Debug"
+)]
+pub struct VotePlanById;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "resources/explorer/graphql/epoch_stake_distribution.graphql",
+    schema_path = "resources/explorer/graphql/schema.graphql",
+    response_derives = "Debug"
+)]
+pub struct EpochStakeDistribution;
+
+#[derive(GraphQLQuery)]
+#[allow(clippy::upper_case_acronyms)]
+#[graphql(
+    query_path = "resources/explorer/graphql/vote_plan_turnout.graphql",
+    schema_path = "resources/explorer/graphql/schema.graphql",
+    response_derives = "Debug"
+)]
+pub struct VotePlanTurnout;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "resources/explorer/graphql/block_by_id.graphql",
+    schema_path = "resources/explorer/graphql/schema.graphql",
+    response_derives = "Debug"
+)]
+pub struct BlockById;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "resources/explorer/graphql/transactions_in_block.graphql",
+    schema_path = "resources/explorer/graphql/schema.graphql",
+    response_derives = "Debug"
+)]
+pub struct TransactionsInBlock;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "resources/explorer/graphql/pool_certificates.graphql",
+    schema_path = "resources/explorer/graphql/schema.graphql",
+    response_derives = "Debug"
+)]
+pub struct PoolCertificates;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "resources/explorer/graphql/blocks_by_epoch_page.graphql",
+    schema_path = "resources/explorer/graphql/schema.graphql",
+    response_derives = "Debug"
+)]
+pub struct BlocksByEpochPage;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "resources/explorer/graphql/committee_ids.graphql",
+    schema_path = "resources/explorer/graphql/schema.graphql",
+    response_derives = "Debug"
+)]
+pub struct CommitteeIds;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "resources/explorer/graphql/version.graphql",
+    schema_path = "resources/explorer/graphql/schema.graphql",
+    response_derives = "Debug"
+)]
+pub struct Version;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    query_path = "resources/explorer/graphql/epoch_rewards.graphql",
+    schema_path = "resources/explorer/graphql/schema.graphql",
+    response_derives = "Debug"
+)]
+pub struct EpochRewards;
@@ -0,0 +1,121 @@
+//! Explorer responses saved to disk, to run explorer-dependent tests
+//! against recorded data rather than a live explorer.
+//!
+//! Each response is kept in its own file under the fixtures directory,
+//! named after the operation and a hash of its variables, e.g.
+//! `LastBlock-0d1a6c3f5e2b7a94.json`.
+
+use super::{Explorer, ExplorerError};
+use chain_crypto::hash::Blake2b256;
+use graphql_client::QueryBody;
+use jormungandr_lib::crypto::hash::Hash;
+use serde::Serialize;
+use std::{
+    io::ErrorKind,
+    ops::{Deref, DerefMut},
+    path::{Path, PathBuf},
+};
+
+/// Length of the variables hash kept in fixture names, in hexadecimal
+/// characters.
+const VARIABLES_HASH_LEN: usize = 16;
+
+#[derive(Clone, Debug)]
+pub(super) enum Fixtures {
+    /// Responses are saved under the directory as they are received.
+    Capture(PathBuf),
+    /// Responses are read from the directory, nothing is sent.
+    Replay(PathBuf),
+}
+
+/// Name of the file keeping the response to `query`.
+pub(super) fn fixture_name<T: Serialize>(query: &QueryBody<T>) -> Result<String, ExplorerError> {
+    let variables = serde_json::to_vec(&query.variables)?;
+    let hash = Hash::from(Blake2b256::new(&variables)).to_string();
+    Ok(format!(
+        "{}-{}.json",
+        query.operation_name,
+        &hash[..VARIABLES_HASH_LEN]
+    ))
+}
+
+pub(super) fn write_fixture(dir: &Path, name: &str, body: &str) -> Result<(), ExplorerError> {
+    let path = dir.join(name);
+    std::fs::write(&path, body).map_err(|source| ExplorerError::FixtureIo { path, source })
+}
+
+pub(super) fn read_fixture(dir: &Path, name: &str) -> Result<String, ExplorerError> {
+    let path = dir.join(name);
+    std::fs::read_to_string(&path).map_err(|source| match source.kind() {
+        ErrorKind::NotFound => ExplorerError::MissingFixture(path),
+        _ => ExplorerError::FixtureIo { path, source },
+    })
+}
+
+/// Explorer answering its queries with the responses captured by
+/// [`Explorer::capture_to`]. It has the query methods of [`Explorer`], and
+/// fails with [`ExplorerError::MissingFixture`] on queries that were not
+/// captured with the same variables.
+///
+/// Nothing is ever sent, so anything which is not a query, such as
+/// subscriptions or checking the explorer is alive, has no explorer to talk
+/// to.
+pub struct MockExplorer {
+    explorer: Explorer,
+}
+
+impl MockExplorer {
+    pub fn from_fixtures<P: Into<PathBuf>>(dir: P) -> Result<Self, ExplorerError> {
+        let dir = dir.into();
+        if !dir.is_dir() {
+            return Err(ExplorerError::MissingFixture(dir));
+        }
+        Ok(Self {
+            explorer: Explorer {
+                fixtures: Some(Fixtures::Replay(dir)),
+                ..Explorer::offline()
+            },
+        })
+    }
+}
+
+impl Deref for MockExplorer {
+    type Target = Explorer;
+
+    fn deref(&self) -> &Explorer {
+        &self.explorer
+    }
+}
+
+impl DerefMut for MockExplorer {
+    fn deref_mut(&mut self) -> &mut Explorer {
+        &mut self.explorer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    pub fn fixtures_are_named_after_operation_and_variables() {
+        let query = |variables| QueryBody {
+            variables,
+            query: "",
+            operation_name: "Epoch",
+        };
+        let name = fixture_name(&query(json!({ "id": "1" }))).unwrap();
+        assert!(name.starts_with("Epoch-") && name.ends_with(".json"));
+        assert_eq!(name, fixture_name(&query(json!({ "id": "1" }))).unwrap());
+        assert_ne!(name, fixture_name(&query(json!({ "id": "2" }))).unwrap());
+
+        let dir = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            read_fixture(dir.path(), &name),
+            Err(ExplorerError::MissingFixture(_))
+        ));
+        write_fixture(dir.path(), &name, "{}").unwrap();
+        assert_eq!(read_fixture(dir.path(), &name).unwrap(), "{}");
+    }
+}
@@ -0,0 +1,97 @@
+use serde::Deserialize;
+
+pub(super) const INTROSPECTION_QUERY: &str = r#"query IntrospectionQuery {
+  __schema {
+    queryType {
+      name
+    }
+    types {
+      name
+      kind
+      fields(includeDeprecated: true) {
+        name
+      }
+    }
+  }
+}"#;
+
+pub(super) const INTROSPECTION_OPERATION_NAME: &str = "IntrospectionQuery";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct IntrospectionData {
+    #[serde(rename = "__schema")]
+    schema: RawSchema,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawSchema {
+    query_type: RawTypeRef,
+    types: Vec<RawType>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawTypeRef {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawType {
+    name: String,
+    kind: String,
+    fields: Option<Vec<RawTypeRef>>,
+}
+
+/// Types and fields of the schema served by a running explorer.
+#[derive(Debug, Clone)]
+pub struct IntrospectionResult {
+    pub query_type: String,
+    pub types: Vec<IntrospectedType>,
+}
+
+#[derive(Debug, Clone)]
+pub struct IntrospectedType {
+    pub name: String,
+    /// GraphQL type kind, e.g. `OBJECT`, `UNION` or `SCALAR`.
+    pub kind: String,
+    /// Field names, empty for types without fields.
+    pub fields: Vec<String>,
+}
+
+impl IntrospectionResult {
+    pub fn type_named(&self, name: &str) -> Option<&IntrospectedType> {
+        self.types.iter().find(|ty| ty.name == name)
+    }
+
+    /// Whether `type_name` exists and has a field called `field`. Meant to
+    /// check a field is served before querying it, e.g. `("Query", "votePlan")`.
+    pub fn has_field(&self, type_name: &str, field: &str) -> bool {
+        self.type_named(type_name)
+            .map(|ty| ty.fields.iter().any(|name| name == field))
+            .unwrap_or(false)
+    }
+}
+
+impl From<IntrospectionData> for IntrospectionResult {
+    fn from(data: IntrospectionData) -> Self {
+        Self {
+            query_type: data.schema.query_type.name,
+            types: data
+                .schema
+                .types
+                .into_iter()
+                .map(|ty| IntrospectedType {
+                    name: ty.name,
+                    kind: ty.kind,
+                    fields: ty
+                        .fields
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|field| field.name)
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
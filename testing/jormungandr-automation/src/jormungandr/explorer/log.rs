@@ -0,0 +1,81 @@
+//! Query logging for the explorer clients.
+//!
+//! When a global `tracing` subscriber is installed, requests and responses
+//! are emitted as debug events inside an `explorer_query` span named after
+//! the operation. Without one they are printed to stdout as before.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Debug;
+
+/// Variable values which serialize to more bytes than this are cut short in
+/// the logs by default.
+pub const DEFAULT_MAX_LOGGED_VARIABLE_LEN: usize = 128;
+
+pub(super) fn query(operation: &str, query: &str, variables: Option<&str>, uri: &str) {
+    if tracing::dispatcher::has_been_set() {
+        let span = tracing::debug_span!("explorer_query", operation);
+        let _enter = span.enter();
+        tracing::debug!(uri, variables, "running query");
+    } else {
+        match variables {
+            Some(variables) => println!(
+                "running query: {:?}, with variables: {}, against: {}",
+                query, variables, uri
+            ),
+            None => println!("running query: {:?}, against: {}", query, uri),
+        }
+    }
+}
+
+/// Renders the query variables as JSON. With `max_len`, every variable
+/// serializing to more than `max_len` bytes is replaced by its beginning
+/// followed by a `…(N bytes omitted)` marker.
+pub(super) fn variables<T: Serialize>(variables: &T, max_len: Option<usize>) -> String {
+    let mut value = match serde_json::to_value(variables) {
+        Ok(value) => value,
+        Err(_) => return "<unserializable variables>".to_string(),
+    };
+    if let (Some(max_len), Value::Object(map)) = (max_len, &mut value) {
+        for variable in map.values_mut() {
+            let rendered = variable.to_string();
+            if rendered.len() > max_len {
+                let mut end = max_len;
+                while !rendered.is_char_boundary(end) {
+                    end -= 1;
+                }
+                *variable = Value::String(format!(
+                    "{}\u{2026}({} bytes omitted)",
+                    &rendered[..end],
+                    rendered.len() - end
+                ));
+            }
+        }
+    }
+    value.to_string()
+}
+
+pub(super) fn response<T: Debug>(response: &T) {
+    let response = format!("{:?}", response);
+    if tracing::dispatcher::has_been_set() {
+        tracing::debug!(response_size = response.len(), %response, "explorer response");
+    } else {
+        println!("Response: {}", response);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn long_variables_are_redacted() {
+        let vars = json!({ "id": "abc", "ids": ["0123456789", "0123456789"] });
+        assert_eq!(
+            variables(&vars, Some(8)),
+            r#"{"id":"abc","ids":"[\"012345…(19 bytes omitted)"}"#
+        );
+        assert_eq!(variables(&vars, None), vars.to_string());
+    }
+}
@@ -0,0 +1,69 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// Latency of the queries sent to the explorer, from sending them to
+/// having read and decoded the whole response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryMetrics {
+    pub count: usize,
+    pub min: Duration,
+    pub max: Duration,
+    pub p50: Duration,
+    pub p95: Duration,
+}
+
+impl QueryMetrics {
+    /// `None` without any sample.
+    fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut samples = samples.to_vec();
+        samples.sort_unstable();
+        // nearest-rank percentile
+        let percentile = |p: usize| samples[((samples.len() * p + 99) / 100).max(1) - 1];
+        Some(QueryMetrics {
+            count: samples.len(),
+            min: samples[0],
+            max: samples[samples.len() - 1],
+            p50: percentile(50),
+            p95: percentile(95),
+        })
+    }
+}
+
+/// Request durations, shared by the clones of a client.
+#[derive(Debug, Clone, Default)]
+pub(super) struct MetricsRecorder {
+    samples: Arc<Mutex<Vec<Duration>>>,
+}
+
+impl MetricsRecorder {
+    pub(super) fn record(&self, duration: Duration) {
+        self.samples.lock().unwrap().push(duration);
+    }
+
+    pub(super) fn metrics(&self) -> Option<QueryMetrics> {
+        QueryMetrics::from_samples(&self.samples.lock().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles_use_the_nearest_rank() {
+        assert_eq!(QueryMetrics::from_samples(&[]), None);
+
+        let samples: Vec<Duration> = (1..=20).rev().map(Duration::from_millis).collect();
+        let metrics = QueryMetrics::from_samples(&samples).unwrap();
+        assert_eq!(metrics.count, 20);
+        assert_eq!(metrics.min, Duration::from_millis(1));
+        assert_eq!(metrics.max, Duration::from_millis(20));
+        assert_eq!(metrics.p50, Duration::from_millis(10));
+        assert_eq!(metrics.p95, Duration::from_millis(19));
+    }
+}
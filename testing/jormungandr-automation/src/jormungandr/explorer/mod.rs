@@ -1,130 +1,496 @@
-use crate::testing::configuration::get_explorer_app;
-
 use self::{
+    builder::{BOOTSTRAP_PROBE_INTERVAL, DEFAULT_BOOTSTRAP_TIMEOUT},
     client::GraphQlClient,
     data::{
-        address, all_blocks, all_stake_pools, all_vote_plans, blocks_by_chain_length, epoch,
-        last_block, settings, stake_pool, transaction_by_id, Address, AllBlocks, AllStakePools,
-        AllVotePlans, BlocksByChainLength, Epoch, LastBlock, Settings, StakePool, TransactionById,
+        address, all_blocks, all_blocks_page, all_stake_pools, all_stake_pools_page,
+        all_vote_plans, block_by_id, blocks_by_chain_length, blocks_by_epoch_page, committee_ids,
+        epoch, epoch_rewards, epoch_stake_distribution, last_block, pool_certificates, settings,
+        stake_pool, transaction_by_id, transactions_by_address, transactions_in_block, version,
+        vote_plan_by_id, vote_plan_turnout, Address, AllBlocks, AllBlocksPage, AllStakePools,
+        AllStakePoolsPage, AllVotePlans, BlockById, BlocksByChainLength, BlocksByEpochPage,
+        CommitteeIds, Epoch, EpochRewards as EpochRewardsQuery, EpochStakeDistribution, LastBlock,
+        PoolCertificates, Settings, StakePool, TransactionById, TransactionsByAddress,
+        TransactionsInBlock, Version, VotePlanById, VotePlanTurnout,
     },
+    fixtures::Fixtures,
+    paging::PagedIter,
 };
+use crate::jormungandr::JormungandrProcess;
+use chain_impl_mockchain::block::BlockDate as LibBlockDate;
 use graphql_client::GraphQLQuery;
 use graphql_client::*;
 use jormungandr_lib::crypto::hash::Hash;
-use jormungandr_lib::interfaces::BlockDate;
+use jormungandr_lib::interfaces::{BlockDate, CommitteeIdDef};
+use jortestkit::process::Wait;
+use process::ExplorerProcess;
 use std::{
-    process::{Command, Stdio},
-    str::FromStr,
+    convert::TryFrom,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    process::ExitStatus,
     sync::Arc,
     time::Duration,
 };
+mod async_explorer;
+mod builder;
 mod client;
+mod fixtures;
 // Macro here expand to something containing PUBLIC/PRIVATE fields that
 // do not respect the naming convention
 #[allow(clippy::upper_case_acronyms)]
 mod data;
+mod introspection;
+mod log;
+mod metrics;
+mod paging;
+mod pool_history;
+mod process;
+mod rewards;
+mod schema;
+mod settings_diff;
+mod subscription;
+mod transport;
+mod vote;
 mod wrappers;
 
-pub use wrappers::LastBlockResponse;
+pub use async_explorer::AsyncExplorer;
+pub use builder::{ExplorerBuilder, DEFAULT_POOL_IDLE_TIMEOUT, DEFAULT_POOL_MAX_IDLE_PER_HOST};
+pub use client::DEFAULT_MAX_RESPONSE_SIZE;
+pub use fixtures::MockExplorer;
+pub use introspection::{IntrospectedType, IntrospectionResult};
+pub use log::DEFAULT_MAX_LOGGED_VARIABLE_LEN;
+pub use metrics::QueryMetrics;
+pub use paging::Page;
+pub use pool_history::{PoolCertificate, PoolHistoryEntry, PoolLifecycle, StakePoolHistory};
+pub use process::{DEFAULT_LOG_BUFFER_LINES, DEFAULT_PANIC_LOG_MAX_SIZE, EXPLORER_APP_ENV};
+pub use rewards::{EpochRewards, EpochRewardsOutcome, PoolReward};
+pub use schema::{compare_schema, schema_check_only, SchemaDiff, SCHEMA_CHECK_ONLY_ENV};
+pub use settings_diff::{diff_settings, SettingsDiff, SettingsMismatch};
+pub use subscription::MAX_RECONNECT_ATTEMPTS;
+pub use transport::{GraphQlTransport, MockTransport};
+pub use vote::{ProposalTally, ProposalTurnout, VoteStatus};
+pub use wrappers::{
+    BlockSummary, BlocksAtChainLengthResponse, BlocksResponse, EpochResponse, LastBlockResponse,
+    SettingsResponse, TipSummary, TransactionsByAddressResponse, TxStatus,
+};
 
 use data::PoolId;
-use jortestkit::{file, process::Wait};
-use serde::Serialize;
-use std::path::Path;
-use std::path::PathBuf;
-use thiserror::Error;
 
-use super::get_available_port;
+pub type StakePoolNode = all_stake_pools_page::AllStakePoolsPageTipAllStakePoolsEdgesNode;
+pub type StakePoolSummary = all_stake_pools::AllStakePoolsTipAllStakePoolsEdgesNode;
+pub type BlockNode = all_blocks_page::AllBlocksPageTipBlocksEdgesNode;
+pub type BlockTransaction = transactions_in_block::TransactionsInBlockBlockTransactionsEdgesNode;
+pub type VotePlanSummary = all_vote_plans::AllVotePlansTipAllVotePlansEdgesNode;
+pub type PoolStake = epoch_stake_distribution::EpochStakeDistributionEpochStakeDistributionPools;
+
+/// Largest number of blocks [`Explorer::blocks_in_range`] accepts to fetch.
+pub const MAX_BLOCK_RANGE: u32 = 10_000;
+const BLOCK_RANGE_PAGE_SIZE: u32 = 500;
+use serde::{de::DeserializeOwned, Serialize};
+use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum ExplorerError {
     #[error("graph client error")]
-    ClientError(#[from] client::GraphQlClientError),
-    #[error("json serializiation error")]
-    SerializationError(#[from] serde_json::Error),
+    ClientError(#[source] client::GraphQlClientError),
+    #[error("query did not complete within its {0:?} deadline")]
+    DeadlineExceeded(Duration),
+    #[error("json serializiation error{}", captured_body(.body))]
+    SerializationError {
+        #[source]
+        source: serde_json::Error,
+        /// Beginning of the response that could not be deserialized, if any.
+        body: Option<String>,
+    },
     #[error("request error")]
     ReqwestError(#[from] reqwest::Error),
+    #[error("graphql query returned errors: {0:?}")]
+    GraphQlErrors(Vec<graphql_client::Error>),
+    #[error("explorer did not start listening on {address} within {timeout:?}")]
+    BootstrapTimeout { address: String, timeout: Duration },
+    #[error("explorer at {address} did not answer within {timeout:?}")]
+    NotAlive { address: String, timeout: Duration },
+    #[error("explorer exited with {status} before accepting connections, stderr:\n{stderr}")]
+    ProcessExited {
+        status: std::process::ExitStatus,
+        stderr: String,
+    },
+    #[error("i/o error")]
+    IoError(#[from] std::io::Error),
+    #[error("explorer schema '{}' is out of date, lines {} differ", .expected.display(), line_ranges(.changed_lines))]
+    SchemaOutOfDate {
+        expected: std::path::PathBuf,
+        changed_lines: Vec<std::ops::Range<usize>>,
+    },
+    #[error("'{0}' is not a valid node address, expected an http(s) url such as 'http://127.0.0.1:8080/'")]
+    InvalidNodeAddress(String),
+    #[error("explorer argument '{0}' is already set by the test framework")]
+    ReservedArgument(String),
+    #[error("unknown explorer log level '{0}', expected one of {levels:?}", levels = builder::LOG_LEVELS)]
+    InvalidLogLevel(String),
+    #[error(
+        "invalid block range {from}..={to}, bounds must be ordered and span at most {max} blocks",
+        max = MAX_BLOCK_RANGE
+    )]
+    InvalidRange { from: u32, to: u32 },
+    #[error("explorer executable not found at '{}'", .0.display())]
+    BinaryNotFound(std::path::PathBuf),
+    #[error("failed to execute explorer process '{}'", .program.display())]
+    SpawnFailed {
+        program: std::path::PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("'{0}' is not a valid hash, expected 64 hexadecimal characters")]
+    InvalidHash(String),
+    #[error("explorer returned a malformed {field}: '{value}'")]
+    MalformedField { field: &'static str, value: String },
+    #[error("'{0}' is not a valid url of an explorer")]
+    InvalidUrl(String),
+    #[error("explorer does not support {0}")]
+    UnsupportedFeature(&'static str),
+    #[error("explorer answered a batch of {expected} queries with {received} responses")]
+    BatchSizeMismatch { expected: usize, received: usize },
+    #[error("explorer was not started by this client")]
+    NoOwnedProcess,
+    #[error("explorer output is already streamed to a file")]
+    FileLoggingAlreadyEnabled,
+    #[error("tip did not reach {target} within {timeout:?}{}", last_tip(.last_seen))]
+    WaitTimeout {
+        target: String,
+        timeout: Duration,
+        /// Last tip the explorer reported, if it answered at all.
+        last_seen: Option<Box<LastBlockResponse>>,
+    },
+    #[error("explorer tips did not converge within {timeout:?}:{}", divergent_tips(.tips))]
+    TipsDiverged {
+        timeout: Duration,
+        /// Uri of each explorer with the last tip it reported, if it
+        /// answered.
+        tips: Vec<(String, Option<Box<LastBlockResponse>>)>,
+    },
+    #[error("subscription connection error")]
+    WebSocketError(#[from] tokio_tungstenite::tungstenite::Error),
+    #[error("subscription to the explorer was closed")]
+    SubscriptionClosed,
+    #[error("explorer rejected the subscription: {0}")]
+    SubscriptionRejected(String),
+    #[error("could not access fixture {path}")]
+    FixtureIo {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("no fixture found at {0}")]
+    MissingFixture(PathBuf),
 }
 
-#[derive(Clone)]
-pub struct Explorer {
-    client: GraphQlClient,
-    print_log: bool,
-    _process: Arc<ExplorerProcess>,
+/// Longest part of a response body kept in [`ExplorerError::SerializationError`].
+const MAX_CAPTURED_BODY_LEN: usize = 4096;
+
+impl From<serde_json::Error> for ExplorerError {
+    fn from(source: serde_json::Error) -> Self {
+        ExplorerError::SerializationError { source, body: None }
+    }
 }
 
-struct ExplorerProcess {
-    handler: Option<std::process::Child>,
-    logs_dir: Option<std::path::PathBuf>,
+fn captured_body(body: &Option<String>) -> String {
+    body.as_ref()
+        .map(|body| format!(", response body: {}", body))
+        .unwrap_or_default()
 }
 
-impl Drop for ExplorerProcess {
-    fn drop(&mut self) {
-        let output = if let Some(mut handler) = self.handler.take() {
-            let _ = handler.kill();
-            handler.wait_with_output().unwrap()
-        } else {
-            return;
-        };
+impl From<client::GraphQlClientError> for ExplorerError {
+    fn from(error: client::GraphQlClientError) -> Self {
+        match error {
+            client::GraphQlClientError::DeadlineExceeded(deadline) => {
+                ExplorerError::DeadlineExceeded(deadline)
+            }
+            error => ExplorerError::ClientError(error),
+        }
+    }
+}
 
-        if std::thread::panicking() {
-            if let Some(logs_dir) = &self.logs_dir {
-                println!(
-                    "persisting explorer logs after panic: {}",
-                    logs_dir.display()
-                );
+fn last_tip(last_seen: &Option<Box<LastBlockResponse>>) -> String {
+    last_seen
+        .as_ref()
+        .map(|tip| {
+            format!(
+                ", last seen tip is at chain length {} and date {}",
+                tip.block().chain_length,
+                tip.block_date()
+            )
+        })
+        .unwrap_or_default()
+}
 
-                std::fs::write(logs_dir.join("explorer.log"), output.stdout)
-                    .unwrap_or_else(|e| eprint!("Could not write explorer logs to disk: {}", e));
-            }
+fn divergent_tips(tips: &[(String, Option<Box<LastBlockResponse>>)]) -> String {
+    tips.iter()
+        .map(|(uri, tip)| match tip {
+            Some(tip) => format!(
+                "\n  {}: {} at chain length {}",
+                uri,
+                tip.block().id,
+                tip.block().chain_length
+            ),
+            None => format!("\n  {}: no answer", uri),
+        })
+        .collect()
+}
+
+fn line_ranges(ranges: &[std::ops::Range<usize>]) -> String {
+    ranges
+        .iter()
+        .map(|range| match range.end - range.start {
+            1 => range.start.to_string(),
+            _ => format!("{}-{}", range.start, range.end - 1),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn decode_body<T: DeserializeOwned>(body: &str) -> Result<T, ExplorerError> {
+    serde_json::from_str(body).map_err(|source| {
+        let mut end = body.len().min(MAX_CAPTURED_BODY_LEN);
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        ExplorerError::SerializationError {
+            source,
+            body: Some(body[..end].to_string()),
         }
+    })
+}
+
+pub(crate) fn parse_field<T: std::str::FromStr>(
+    field: &'static str,
+    value: String,
+) -> Result<T, ExplorerError> {
+    value
+        .parse()
+        .map_err(|_| ExplorerError::MalformedField { field, value })
+}
+
+fn block_summary(block: BlockNode) -> Result<BlockSummary, ExplorerError> {
+    let block_date = LibBlockDate {
+        epoch: parse_field("epoch", block.date.epoch.id)?,
+        slot_id: parse_field("slot", block.date.slot)?,
+    };
+    Ok(BlockSummary {
+        hash: parse_field("block id", block.id)?,
+        chain_length: parse_field("chain length", block.chain_length)?,
+        block_date: BlockDate::from(block_date),
+    })
+}
+
+/// Parses a block or transaction hash given as text, e.g. by a test
+/// configuration, failing with [`ExplorerError::InvalidHash`] before any
+/// query is sent.
+pub fn parse_block_hash(s: &str) -> Result<Hash, ExplorerError> {
+    s.parse()
+        .map_err(|_| ExplorerError::InvalidHash(s.to_string()))
+}
+
+fn into_value_body<T: Serialize>(
+    query: QueryBody<T>,
+) -> Result<QueryBody<serde_json::Value>, ExplorerError> {
+    Ok(QueryBody {
+        variables: serde_json::to_value(query.variables)?,
+        query: query.query,
+        operation_name: query.operation_name,
+    })
+}
+
+/// Extracts the `data` payload of a GraphQL response, turning a non-empty
+/// `errors` array (or a response without any data) into
+/// [`ExplorerError::GraphQlErrors`].
+pub fn into_data<T>(response: Response<T>) -> Result<T, ExplorerError> {
+    match (response.data, response.errors) {
+        (_, Some(errors)) if !errors.is_empty() => Err(ExplorerError::GraphQlErrors(errors)),
+        (Some(data), _) => Ok(data),
+        (None, errors) => Err(ExplorerError::GraphQlErrors(errors.unwrap_or_default())),
     }
 }
 
+/// The `code` extension the explorer attaches to the errors it raises, e.g.
+/// `NOT_FOUND`. Validation errors of the query itself have none.
+fn error_code(error: &graphql_client::Error) -> Option<&str> {
+    error.extensions.as_ref()?.get("code")?.as_str()
+}
+
+/// The explorer reports lookups of unknown ids as a `NOT_FOUND` error on a
+/// non-nullable field.
+fn is_not_found(errors: &[graphql_client::Error]) -> bool {
+    !errors.is_empty()
+        && errors
+            .iter()
+            .all(|error| error_code(error) == Some("NOT_FOUND"))
+}
+
+/// Whether the explorer answered a field it does not serve yet.
+fn is_unimplemented(errors: &[graphql_client::Error]) -> bool {
+    errors
+        .iter()
+        .any(|error| error_code(error) == Some("UNIMPLEMENTED"))
+}
+
+#[derive(Clone)]
+pub struct Explorer {
+    client: GraphQlClient,
+    listen_addr: SocketAddr,
+    print_log: bool,
+    redact_variables: bool,
+    max_logged_variable_len: usize,
+    /// `None` when attached to an explorer this instance did not start.
+    process: Option<Arc<ExplorerProcess>>,
+    /// `None` unless responses are captured or replayed.
+    fixtures: Option<Fixtures>,
+    /// `None` when queries are sent with `client`.
+    transport: Option<Arc<dyn GraphQlTransport>>,
+}
+
 impl Explorer {
     pub fn new(node_address: String, logs_dir: Option<std::path::PathBuf>) -> Explorer {
-        let print_log = true;
-
-        let path = get_explorer_app();
-        let explorer_port = get_available_port();
-        let explorer_listen_address = format!("127.0.0.1:{}", explorer_port);
-
-        let _process = Arc::new(ExplorerProcess {
-            handler: Some(
-                Command::new(path)
-                    .args(&[
-                        "--node",
-                        node_address.as_ref(),
-                        "--binding-address",
-                        explorer_listen_address.as_ref(),
-                        "--log-output",
-                        "stdout",
-                    ])
-                    .stdout(Stdio::piped())
-                    .stderr(Stdio::piped())
-                    .spawn()
-                    .expect("failed to execute explorer process"),
-            ),
-            logs_dir,
-        });
+        Self::try_new(node_address, logs_dir).expect("explorer did not start")
+    }
 
-        let mut wait_bootstrap = Wait::new(Duration::from_secs(1), 10);
-        while !wait_bootstrap.timeout_reached() {
-            if reqwest::blocking::Client::new()
-                .head(format!("http://{}/", &explorer_listen_address))
-                .send()
-                .is_ok()
-            {
-                break;
-            };
+    /// Starts an explorer for the node at `node_address`, reporting a missing
+    /// binary, a malformed node address, a failed spawn or a bootstrap timeout
+    /// as an error.
+    pub fn try_new(
+        node_address: String,
+        logs_dir: Option<std::path::PathBuf>,
+    ) -> Result<Explorer, ExplorerError> {
+        Self::new_with_timeout(node_address, logs_dir, DEFAULT_BOOTSTRAP_TIMEOUT)
+    }
 
-            wait_bootstrap.advance();
+    pub fn new_with_timeout(
+        node_address: String,
+        logs_dir: Option<std::path::PathBuf>,
+        timeout: Duration,
+    ) -> Result<Explorer, ExplorerError> {
+        ExplorerBuilder::new(node_address)
+            .logs_dir(logs_dir)
+            .bootstrap_timeout(timeout)
+            .build()
+    }
+
+    /// Same as [`Explorer::try_new`] for the node REST API listening on
+    /// `node_address`.
+    pub fn try_new_for_addr(
+        node_address: SocketAddr,
+        logs_dir: Option<std::path::PathBuf>,
+    ) -> Result<Explorer, ExplorerError> {
+        ExplorerBuilder::from_socket_addr(node_address)
+            .logs_dir(logs_dir)
+            .build()
+    }
+
+    /// Starts an explorer for `node`, connected to its p2p address. The
+    /// explorer logs go to `logs_dir`, or next to the node logs in the node
+    /// temporary directory if not given.
+    pub fn from_node(
+        node: &JormungandrProcess,
+        logs_dir: Option<std::path::PathBuf>,
+    ) -> Result<Explorer, ExplorerError> {
+        let p2p_address = node.p2p_public_address();
+        let address = jormungandr_lib::multiaddr::to_tcp_socket_addr(&p2p_address)
+            .ok_or_else(|| ExplorerError::InvalidNodeAddress(p2p_address.to_string()))?;
+        ExplorerBuilder::from_socket_addr(address)
+            .logs_dir(logs_dir.or_else(|| node.temp_dir()))
+            .build()
+    }
+
+    pub fn builder<S: Into<String>>(node_address: S) -> ExplorerBuilder {
+        ExplorerBuilder::new(node_address)
+    }
+
+    /// Attaches to an explorer started by someone else, e.g.
+    /// `http://127.0.0.1:8080`. Nothing is spawned, and the explorer is left
+    /// running when the returned client is dropped.
+    pub fn connect(url: String) -> Result<Explorer, ExplorerError> {
+        Self::connect_with_scheme(url, "http", reqwest::blocking::Client::new())
+    }
+
+    /// Same as [`Explorer::connect`] for an explorer served over TLS, e.g.
+    /// `https://explorer.example.com`.
+    pub fn connect_https(url: String) -> Result<Explorer, ExplorerError> {
+        Self::connect_with_scheme(url, "https", reqwest::blocking::Client::new())
+    }
+
+    /// Same as [`Explorer::connect`] for an `http` or `https` url, sending
+    /// every query through `client` as configured by the caller, e.g. with a
+    /// proxy or its own timeouts, instead of a default one.
+    pub fn connect_with_client(
+        url: String,
+        client: reqwest::blocking::Client,
+    ) -> Result<Explorer, ExplorerError> {
+        let scheme = match reqwest::Url::parse(&url) {
+            Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {
+                parsed.scheme().to_string()
+            }
+            _ => return Err(ExplorerError::InvalidUrl(url)),
+        };
+        Self::connect_with_scheme(url, &scheme, client)
+    }
+
+    fn connect_with_scheme(
+        url: String,
+        scheme: &str,
+        client: reqwest::blocking::Client,
+    ) -> Result<Explorer, ExplorerError> {
+        let parsed =
+            reqwest::Url::parse(&url).map_err(|_| ExplorerError::InvalidUrl(url.clone()))?;
+        if parsed.scheme() != scheme {
+            return Err(ExplorerError::InvalidUrl(url));
+        }
+        let (host, port) = match (parsed.host_str(), parsed.port_or_known_default()) {
+            (Some(host), Some(port)) => (host.to_string(), port),
+            _ => return Err(ExplorerError::InvalidUrl(url)),
+        };
+        let listen_addr = parsed
+            .socket_addrs(|| None)
+            .ok()
+            .and_then(|addrs| addrs.into_iter().next())
+            .ok_or_else(|| ExplorerError::InvalidUrl(url.clone()))?;
+
+        Ok(Explorer {
+            // keep the host name rather than the resolved address, TLS
+            // certificates are checked against it
+            client: GraphQlClient::with_scheme(scheme, format!("{}:{}", host, port), client),
+            listen_addr,
+            print_log: true,
+            redact_variables: true,
+            max_logged_variable_len: DEFAULT_MAX_LOGGED_VARIABLE_LEN,
+            process: None,
+            fixtures: None,
+            transport: None,
+        })
+    }
+
+    /// Explorer sending its queries through `transport` rather than over
+    /// HTTP, e.g. a [`MockTransport`] answering them with canned responses.
+    /// Nothing else reaches an explorer, so subscriptions or checking the
+    /// explorer is alive have none to talk to.
+    pub fn with_transport<T: GraphQlTransport + 'static>(transport: T) -> Explorer {
+        Explorer {
+            transport: Some(Arc::new(transport)),
+            ..Self::offline()
         }
+    }
 
+    /// Explorer bound to no address, which cannot reach any explorer.
+    fn offline() -> Explorer {
+        let listen_addr: SocketAddr = ([127, 0, 0, 1], 0).into();
         Explorer {
-            client: GraphQlClient::new(explorer_listen_address),
-            print_log,
-            _process,
+            client: GraphQlClient::new(listen_addr.to_string()),
+            listen_addr,
+            print_log: true,
+            redact_variables: true,
+            max_logged_variable_len: DEFAULT_MAX_LOGGED_VARIABLE_LEN,
+            process: None,
+            fixtures: None,
+            transport: None,
         }
     }
 
@@ -132,6 +498,99 @@ impl Explorer {
         self.client.base_url()
     }
 
+    /// Whether the explorer currently answers requests. Cheap enough to call
+    /// before every batch of queries.
+    pub fn is_alive(&self) -> bool {
+        self.client
+            .http_client()
+            .head(self.client.root_url())
+            .send()
+            .is_ok()
+    }
+
+    /// Waits up to `timeout` for the explorer to answer, failing with
+    /// [`ExplorerError::NotAlive`] if it never does.
+    pub fn wait_until_alive(&self, timeout: Duration) -> Result<(), ExplorerError> {
+        let attempts = (timeout.as_millis() / BOOTSTRAP_PROBE_INTERVAL.as_millis()).max(1);
+        let mut wait = Wait::new(BOOTSTRAP_PROBE_INTERVAL, attempts as _);
+        loop {
+            if self.is_alive() {
+                return Ok(());
+            }
+            if wait.timeout_reached() {
+                return Err(ExplorerError::NotAlive {
+                    address: self.client.root_url().to_string(),
+                    timeout,
+                });
+            }
+            wait.advance();
+        }
+    }
+
+    /// Address the explorer process was told to bind to, or the one it was
+    /// connected to.
+    pub fn listen_addr(&self) -> SocketAddr {
+        self.listen_addr
+    }
+
+    pub fn port(&self) -> u16 {
+        self.listen_addr.port()
+    }
+
+    /// Streams the explorer output to `path` for the rest of the run instead
+    /// of only dumping it to `logs_dir` when a test panics.
+    pub fn enable_file_logging<P: AsRef<Path>>(&self, path: P) -> Result<(), ExplorerError> {
+        self.process
+            .as_ref()
+            .ok_or(ExplorerError::NoOwnedProcess)?
+            .enable_file_logging(path.as_ref())
+    }
+
+    /// Exit status of the explorer process if it has exited, checked
+    /// without blocking. `None` while it runs, and for an explorer attached
+    /// with [`Explorer::connect`].
+    pub fn process_status(&self) -> Option<ExitStatus> {
+        self.process.as_ref().and_then(|process| process.status())
+    }
+
+    /// Last `lines` lines the explorer process wrote to stderr, e.g. to
+    /// explain an unexpected exit in a failure message. `None` for an
+    /// explorer attached with [`Explorer::connect`].
+    pub fn stderr_tail(&self, lines: usize) -> Option<String> {
+        self.process
+            .as_ref()
+            .map(|process| process.stderr_tail(lines))
+    }
+
+    /// Lines most recently written by the explorer process to stdout and
+    /// stderr, oldest first, up to the number kept in memory (see
+    /// [`ExplorerBuilder::log_buffer_lines`]). Empty for an explorer attached
+    /// with [`Explorer::connect`].
+    pub fn recent_logs(&self) -> Vec<String> {
+        self.process
+            .as_ref()
+            .map(|process| process.recent_logs(usize::MAX))
+            .unwrap_or_default()
+    }
+
+    /// Whether the output kept in memory is written to the logs directory
+    /// when the explorer is dropped during a panic, which is the default.
+    /// Does nothing for an explorer this instance did not start.
+    pub fn set_persist_logs_on_panic(&self, persist: bool) {
+        if let Some(process) = &self.process {
+            process.set_persist_logs_on_panic(persist);
+        }
+    }
+
+    /// Caps what is written to the logs directory on panic to the last
+    /// lines fitting in `max_size` bytes, [`DEFAULT_PANIC_LOG_MAX_SIZE`]
+    /// by default.
+    pub fn set_panic_log_max_size(&self, max_size: usize) {
+        if let Some(process) = &self.process {
+            process.set_panic_log_max_size(max_size);
+        }
+    }
+
     pub fn disable_logs(&mut self) {
         self.print_log = false;
         self.client.disable_print();
@@ -142,12 +601,93 @@ impl Explorer {
         self.client.enable_print();
     }
 
+    /// Whether logged query variables longer than the limit set with
+    /// [`Explorer::set_max_logged_variable_len`] are cut short, which is the
+    /// default. The query itself is always logged in full.
+    pub fn redact_variables(&mut self, redact: bool) {
+        self.redact_variables = redact;
+    }
+
+    pub fn set_max_logged_variable_len(&mut self, max_len: usize) {
+        self.max_logged_variable_len = max_len;
+    }
+
+    /// Largest response body, in bytes after decompression, read from the
+    /// explorer. Larger responses fail with
+    /// [`GraphQlClientError::ResponseTooLarge`](client::GraphQlClientError::ResponseTooLarge)
+    /// before being buffered in full. Defaults to
+    /// [`DEFAULT_MAX_RESPONSE_SIZE`].
+    pub fn set_max_response_size(&mut self, max_size: u64) {
+        self.client.set_max_response_size(max_size);
+    }
+
+    /// Upper bound on the time spent on each query, including reading its
+    /// response, past which it fails with [`ExplorerError::DeadlineExceeded`].
+    /// Until then, queries failing to connect to the explorer are retried.
+    /// It takes precedence over the timeout of the HTTP client the explorer
+    /// was given. No deadline by default.
+    pub fn set_query_deadline(&mut self, deadline: Option<Duration>) {
+        self.client.set_query_deadline(deadline);
+    }
+
+    /// Saves the response to every query from now on as a file under `dir`,
+    /// named after the query and its variables, for
+    /// [`MockExplorer::from_fixtures`] to replay. Responses already captured
+    /// under the same name are overwritten. Only the query methods are
+    /// captured, not [`Explorer::run`] nor [`Explorer::run_raw`].
+    pub fn capture_to<P: Into<PathBuf>>(&mut self, dir: P) -> Result<(), ExplorerError> {
+        let path = dir.into();
+        std::fs::create_dir_all(&path).map_err(|source| ExplorerError::FixtureIo {
+            path: path.clone(),
+            source,
+        })?;
+        self.fixtures = Some(Fixtures::Capture(path));
+        Ok(())
+    }
+
+    /// Records the latency of every query from now on, up to its response
+    /// being decoded, to be read with [`Explorer::query_metrics`]. Responses
+    /// returned unread by [`Explorer::run`] are left out. Off by default.
+    pub fn collect_metrics(&mut self, collect: bool) {
+        self.client.collect_metrics(collect);
+    }
+
+    /// Count, minimum, maximum, median and 95th percentile latency of the
+    /// queries sent while metrics were collected, `None` before any.
+    pub fn query_metrics(&self) -> Option<QueryMetrics> {
+        self.client.query_metrics()
+    }
+
+    /// Requests gzip-compressed responses, which is the default.
+    pub fn enable_compression(&mut self) {
+        self.client.enable_compression();
+    }
+
+    /// Requests uncompressed responses, useful when debugging raw payloads.
+    pub fn disable_compression(&mut self) {
+        self.client.disable_compression();
+    }
+
     pub fn print_request<T: Serialize>(&self, query: &QueryBody<T>) {
         if !self.print_log {
             return;
         }
 
-        println!("running query: {:?}, against: {}", query.query, self.uri());
+        let variables = log::variables(&query.variables, self.logged_variable_len());
+        log::query(
+            query.operation_name,
+            query.query,
+            Some(&variables),
+            &self.uri(),
+        );
+    }
+
+    fn logged_variable_len(&self) -> Option<usize> {
+        if self.redact_variables {
+            Some(self.max_logged_variable_len)
+        } else {
+            None
+        }
     }
 
     pub fn address<S: Into<String>>(
@@ -158,42 +698,267 @@ impl Explorer {
             bech32: bech32_address.into(),
         });
         self.print_request(&query);
-        let response = self.client.run(query).map_err(ExplorerError::ClientError)?;
-        let response_body: Response<address::ResponseData> = response.json()?;
+        let response_body: Response<address::ResponseData> = self.query(query)?;
         self.print_log(&response_body);
         Ok(response_body)
     }
 
+    /// First `limit` stake pools, in the order the explorer returns them,
+    /// which is not guaranteed to be stable between runs. See
+    /// [`Explorer::stake_pools_sorted`] for a deterministic order.
     pub fn stake_pools(
         &self,
         limit: i64,
     ) -> Result<Response<all_stake_pools::ResponseData>, ExplorerError> {
         let query = AllStakePools::build_query(all_stake_pools::Variables { first: limit });
         self.print_request(&query);
-        let response = self.client.run(query).map_err(ExplorerError::ClientError)?;
-        let response_body = response.json()?;
+        let response_body = self.query(query)?;
         self.print_log(&response_body);
         Ok(response_body)
     }
 
+    /// Same pools as [`Explorer::stake_pools`], sorted by pool id.
+    pub fn stake_pools_sorted(&self, limit: i64) -> Result<Vec<StakePoolSummary>, ExplorerError> {
+        let mut pools: Vec<StakePoolSummary> = into_data(self.stake_pools(limit)?)?
+            .tip
+            .all_stake_pools
+            .edges
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .map(|edge| edge.node)
+            .collect();
+        pools.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(pools)
+    }
+
+    pub fn stake_pools_page(
+        &self,
+        first: i64,
+        after: Option<String>,
+    ) -> Result<Page<StakePoolNode>, ExplorerError> {
+        let query =
+            AllStakePoolsPage::build_query(all_stake_pools_page::Variables { first, after });
+        self.print_request(&query);
+        let response_body: Response<all_stake_pools_page::ResponseData> = self.query(query)?;
+        self.print_log(&response_body);
+        let connection = into_data(response_body)?.tip.all_stake_pools;
+        Ok(Page {
+            items: connection
+                .edges
+                .unwrap_or_default()
+                .into_iter()
+                .flatten()
+                .map(|edge| edge.node)
+                .collect(),
+            end_cursor: connection.page_info.end_cursor,
+            has_next_page: connection.page_info.has_next_page,
+        })
+    }
+
+    /// Iterates over every stake pool, fetching `page_size` pools per request.
+    pub fn all_stake_pools_paged(
+        &self,
+        page_size: i64,
+    ) -> impl Iterator<Item = Result<StakePoolNode, ExplorerError>> + '_ {
+        PagedIter::new(move |after| self.stake_pools_page(page_size, after))
+    }
+
     pub fn blocks(&self, limit: i64) -> Result<Response<all_blocks::ResponseData>, ExplorerError> {
         let query = AllBlocks::build_query(all_blocks::Variables { last: limit });
         self.print_request(&query);
-        let response = self.client.run(query).map_err(ExplorerError::ClientError)?;
-        let response_body = response.json()?;
+        let response_body = self.query(query)?;
         self.print_log(&response_body);
         Ok(response_body)
     }
 
+    /// Same as [`blocks`](Self::blocks), wrapped for typed access to the
+    /// block hashes.
+    pub fn blocks_response(&self, limit: i64) -> Result<BlocksResponse, ExplorerError> {
+        self.blocks(limit).map(BlocksResponse::new)
+    }
+
+    /// Fetches `first` blocks starting from genesis, or from the block
+    /// following the `after` cursor.
+    pub fn blocks_page(
+        &self,
+        first: i64,
+        after: Option<String>,
+    ) -> Result<Page<BlockNode>, ExplorerError> {
+        let query = AllBlocksPage::build_query(all_blocks_page::Variables { first, after });
+        self.print_request(&query);
+        let response_body: Response<all_blocks_page::ResponseData> = self.query(query)?;
+        self.print_log(&response_body);
+        let connection = into_data(response_body)?.tip.blocks;
+        Ok(Page {
+            items: connection
+                .edges
+                .unwrap_or_default()
+                .into_iter()
+                .flatten()
+                .map(|edge| edge.node)
+                .collect(),
+            end_cursor: connection.page_info.end_cursor,
+            has_next_page: connection.page_info.has_next_page,
+        })
+    }
+
+    /// Iterates over every block from genesis to the tip, fetching
+    /// `page_size` blocks per request. Only one page is held in memory at
+    /// any time, and a failed request is yielded as an `Err` item which
+    /// ends the iteration.
+    pub fn iter_all_blocks(
+        &self,
+        page_size: i64,
+    ) -> impl Iterator<Item = Result<BlockSummary, ExplorerError>> + '_ {
+        PagedIter::new(move |after| self.blocks_page(page_size, after))
+            .map(|block| block.and_then(block_summary))
+    }
+
     pub fn last_block(&self) -> Result<LastBlockResponse, ExplorerError> {
         let query = LastBlock::build_query(last_block::Variables);
         self.print_request(&query);
-        let response = self.client.run(query).map_err(ExplorerError::ClientError)?;
-        let response_body = response.json()?;
+        let response_body = self.query(query)?;
         self.print_log(&response_body);
         Ok(LastBlockResponse::new(response_body))
     }
 
+    /// Same as [`Explorer::block`] for a hash given as text.
+    pub fn block_by_hash_str(
+        &self,
+        hash: &str,
+    ) -> Result<Option<block_by_id::BlockByIdBlock>, ExplorerError> {
+        self.block(parse_block_hash(hash)?)
+    }
+
+    /// Block `hash` with its header fields and transaction ids, or `None`
+    /// if the explorer does not know it. Following `previous_block` walks the
+    /// chain backward from [`Explorer::last_block`].
+    pub fn block(&self, hash: Hash) -> Result<Option<block_by_id::BlockByIdBlock>, ExplorerError> {
+        let query = BlockById::build_query(block_by_id::Variables {
+            id: hash.to_string(),
+        });
+        self.print_request(&query);
+        let response_body: Response<block_by_id::ResponseData> = self.query(query)?;
+        self.print_log(&response_body);
+        match into_data(response_body) {
+            Ok(data) => Ok(Some(data.block)),
+            Err(ExplorerError::GraphQlErrors(errors)) if is_not_found(&errors) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Block produced at `date`, or `None` if its slot is empty or the
+    /// explorer does not know the epoch. The blocks of the epoch are looked
+    /// up in slot order until `date` is reached.
+    pub fn block_at(
+        &self,
+        date: BlockDate,
+    ) -> Result<Option<block_by_id::BlockByIdBlock>, ExplorerError> {
+        let target = date.slot();
+        let mut after = None;
+        loop {
+            let page = match self.blocks_by_epoch_page(date.epoch(), after)? {
+                Some(page) => page,
+                None => return Ok(None),
+            };
+            for block in page.items {
+                let slot: u32 = parse_field("slot", block.date.slot)?;
+                if slot == target {
+                    return self.block(parse_field("id", block.id)?);
+                }
+                if slot > target {
+                    return Ok(None);
+                }
+            }
+            if !page.has_next_page || page.end_cursor.is_none() {
+                return Ok(None);
+            }
+            after = page.end_cursor;
+        }
+    }
+
+    fn blocks_by_epoch_page(
+        &self,
+        epoch: u32,
+        after: Option<String>,
+    ) -> Result<
+        Option<Page<blocks_by_epoch_page::BlocksByEpochPageTipBlocksByEpochEdgesNode>>,
+        ExplorerError,
+    > {
+        let query = BlocksByEpochPage::build_query(blocks_by_epoch_page::Variables {
+            epoch: epoch.to_string(),
+            first: BLOCK_RANGE_PAGE_SIZE as i64,
+            after,
+        });
+        self.print_request(&query);
+        let response_body: Response<blocks_by_epoch_page::ResponseData> = self.query(query)?;
+        self.print_log(&response_body);
+        let connection = match into_data(response_body) {
+            Ok(data) => match data.tip.blocks_by_epoch {
+                Some(connection) => connection,
+                None => return Ok(None),
+            },
+            Err(ExplorerError::GraphQlErrors(errors)) if is_not_found(&errors) => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        Ok(Some(Page {
+            items: connection
+                .edges
+                .unwrap_or_default()
+                .into_iter()
+                .flatten()
+                .map(|edge| edge.node)
+                .collect(),
+            end_cursor: connection.page_info.end_cursor,
+            has_next_page: connection.page_info.has_next_page,
+        }))
+    }
+
+    /// First `first` transactions of block `hash`, with their inputs and
+    /// outputs, or `None` if the explorer does not know the block.
+    pub fn transactions_in_block(
+        &self,
+        hash: Hash,
+        first: i64,
+    ) -> Result<Option<Page<BlockTransaction>>, ExplorerError> {
+        self.transactions_in_block_page(hash, first, None)
+    }
+
+    /// Fetches `first` transactions of block `hash` following the `after`
+    /// cursor of a previous page.
+    pub fn transactions_in_block_page(
+        &self,
+        hash: Hash,
+        first: i64,
+        after: Option<String>,
+    ) -> Result<Option<Page<BlockTransaction>>, ExplorerError> {
+        let query = TransactionsInBlock::build_query(transactions_in_block::Variables {
+            id: hash.to_string(),
+            first,
+            after,
+        });
+        self.print_request(&query);
+        let response_body: Response<transactions_in_block::ResponseData> = self.query(query)?;
+        self.print_log(&response_body);
+        let connection = match into_data(response_body) {
+            Ok(data) => data.block.transactions,
+            Err(ExplorerError::GraphQlErrors(errors)) if is_not_found(&errors) => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        Ok(Some(Page {
+            items: connection
+                .edges
+                .unwrap_or_default()
+                .into_iter()
+                .flatten()
+                .map(|edge| edge.node)
+                .collect(),
+            end_cursor: connection.page_info.end_cursor,
+            has_next_page: connection.page_info.has_next_page,
+        }))
+    }
+
     pub fn blocks_at_chain_length(
         &self,
         length: u32,
@@ -202,12 +967,105 @@ impl Explorer {
             length: length.to_string(),
         });
         self.print_request(&query);
-        let response = self.client.run(query).map_err(ExplorerError::ClientError)?;
-        let response_body = response.json()?;
+        let response_body = self.query(query)?;
         self.print_log(&response_body);
         Ok(response_body)
     }
 
+    /// Same as [`blocks_at_chain_length`](Self::blocks_at_chain_length),
+    /// wrapped for typed access to the block hashes.
+    pub fn blocks_at_chain_length_response(
+        &self,
+        length: u32,
+    ) -> Result<BlocksAtChainLengthResponse, ExplorerError> {
+        self.blocks_at_chain_length(length)
+            .map(BlocksAtChainLengthResponse::new)
+    }
+
+    /// Active stake delegated to each pool at `epoch_number`. An epoch the
+    /// explorer knows nothing about, e.g. one before the stake distribution
+    /// was in place, yields an empty distribution.
+    ///
+    /// Note that the explorer in this tree does not serve the stake
+    /// distribution yet, which yields [`ExplorerError::UnsupportedFeature`].
+    pub fn epoch_stake_distribution(
+        &self,
+        epoch_number: u32,
+    ) -> Result<Vec<PoolStake>, ExplorerError> {
+        let query = EpochStakeDistribution::build_query(epoch_stake_distribution::Variables {
+            id: epoch_number.to_string(),
+        });
+        self.print_request(&query);
+        let response_body: Response<epoch_stake_distribution::ResponseData> = self.query(query)?;
+        self.print_log(&response_body);
+        match into_data(response_body) {
+            Ok(data) => Ok(data.epoch.stake_distribution.pools),
+            Err(ExplorerError::GraphQlErrors(errors)) if is_not_found(&errors) => Ok(Vec::new()),
+            Err(ExplorerError::GraphQlErrors(errors)) if is_unimplemented(&errors) => Err(
+                ExplorerError::UnsupportedFeature("epoch stake distribution"),
+            ),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Rewards distributed at the end of `epoch`: the total, the part going
+    /// to the treasury and the reward of each pool. Epochs ending before
+    /// rewards began are distributed with all amounts at zero.
+    ///
+    /// Note that the explorer in this tree does not keep track of the reward
+    /// pots, so any epoch ending after rewards began yields
+    /// [`ExplorerError::UnsupportedFeature`].
+    pub fn epoch_rewards(&self, epoch: u32) -> Result<EpochRewardsOutcome, ExplorerError> {
+        let query = EpochRewardsQuery::build_query(epoch_rewards::Variables {
+            id: epoch.to_string(),
+        });
+        self.print_request(&query);
+        let response_body: Response<epoch_rewards::ResponseData> = self.query(query)?;
+        self.print_log(&response_body);
+        match into_data(response_body) {
+            Ok(data) => match data.epoch.rewards {
+                Some(rewards) => Ok(EpochRewardsOutcome::Distributed(EpochRewards::new(
+                    rewards,
+                )?)),
+                None => Ok(EpochRewardsOutcome::NotYetDistributed),
+            },
+            Err(ExplorerError::GraphQlErrors(errors)) if is_unimplemented(&errors) => {
+                Err(ExplorerError::UnsupportedFeature("epoch rewards"))
+            }
+            Err(ExplorerError::GraphQlErrors(errors)) => {
+                self.unless_field_missing("Epoch", "rewards", errors, "epoch rewards")
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Fetches the blocks with chain lengths in `from..=to` of the tip branch,
+    /// ordered by chain length. The range is requested in pages of
+    /// connection blocks rather than one query per height.
+    pub fn blocks_in_range(&self, from: u32, to: u32) -> Result<Vec<BlockNode>, ExplorerError> {
+        if from > to || to - from >= MAX_BLOCK_RANGE {
+            return Err(ExplorerError::InvalidRange { from, to });
+        }
+
+        let mut blocks = Vec::with_capacity((to - from + 1) as usize);
+        // the connection cursor is the chain length of the block, and `after`
+        // is exclusive
+        let mut after = from.checked_sub(1).map(|length| length.to_string());
+        loop {
+            let remaining = to - from + 1 - blocks.len() as u32;
+            let page = self.blocks_page(remaining.min(BLOCK_RANGE_PAGE_SIZE) as i64, after)?;
+            let done = page.items.is_empty() || !page.has_next_page;
+            blocks.extend(page.items);
+            after = page.end_cursor;
+            if done || after.is_none() || blocks.len() as u32 > to - from {
+                break;
+            }
+        }
+
+        blocks.sort_by_key(|block| block.chain_length.parse::<u32>().unwrap_or_default());
+        Ok(blocks)
+    }
+
     pub fn epoch(
         &self,
         epoch_number: u32,
@@ -218,12 +1076,21 @@ impl Explorer {
             blocks_limit: limit,
         });
         self.print_request(&query);
-        let response = self.client.run(query).map_err(ExplorerError::ClientError)?;
-        let response_body = response.json()?;
+        let response_body = self.query(query)?;
         self.print_log(&response_body);
         Ok(response_body)
     }
 
+    /// Same as [`epoch`](Self::epoch), wrapped for typed access to the epoch
+    /// id and its blocks.
+    pub fn epoch_response(
+        &self,
+        epoch_number: u32,
+        limit: i64,
+    ) -> Result<EpochResponse, ExplorerError> {
+        self.epoch(epoch_number, limit).map(EpochResponse::new)
+    }
+
     pub fn stake_pool(
         &self,
         id: PoolId,
@@ -231,33 +1098,266 @@ impl Explorer {
     ) -> Result<Response<stake_pool::ResponseData>, ExplorerError> {
         let query = StakePool::build_query(stake_pool::Variables { id, first: limit });
         self.print_request(&query);
-        let response = self.client.run(query).map_err(ExplorerError::ClientError)?;
-        let response_body = response.json()?;
+        let response_body = self.query(query)?;
         self.print_log(&response_body);
         Ok(response_body)
     }
 
+    /// Registration, update and retirement certificates of the pool, in the
+    /// order they were included in the chain, or `None` if the pool never
+    /// registered. The explorer has no index of pool certificates so every
+    /// block of the chain is fetched, which is only practical on the small
+    /// chains of tests.
+    pub fn stake_pool_history(
+        &self,
+        id: PoolId,
+    ) -> Result<Option<StakePoolHistory>, ExplorerError> {
+        let mut entries = Vec::new();
+        for block in PagedIter::new(|after| self.pool_certificates_page(after)) {
+            entries.extend(pool_history::pool_entries(block?, &id)?);
+        }
+        Ok(Some(entries)
+            .filter(|entries| !entries.is_empty())
+            .map(StakePoolHistory::new))
+    }
+
+    fn pool_certificates_page(
+        &self,
+        after: Option<String>,
+    ) -> Result<Page<pool_certificates::PoolCertificatesTipBlocksEdgesNode>, ExplorerError> {
+        let query = PoolCertificates::build_query(pool_certificates::Variables {
+            first: BLOCK_RANGE_PAGE_SIZE as i64,
+            after,
+        });
+        self.print_request(&query);
+        let response_body: Response<pool_certificates::ResponseData> = self.query(query)?;
+        self.print_log(&response_body);
+        let connection = into_data(response_body)?.tip.blocks;
+        Ok(Page {
+            items: connection
+                .edges
+                .unwrap_or_default()
+                .into_iter()
+                .flatten()
+                .map(|edge| edge.node)
+                .collect(),
+            end_cursor: connection.page_info.end_cursor,
+            has_next_page: connection.page_info.has_next_page,
+        })
+    }
+
+    /// Ids of the committee members set in block0, as listed by the node
+    /// REST `committees` endpoint. A chain without a committee yields an
+    /// empty list.
+    pub fn committee_ids(&self) -> Result<Vec<CommitteeIdDef>, ExplorerError> {
+        let query = CommitteeIds::build_query(committee_ids::Variables);
+        self.print_request(&query);
+        let response_body: Response<committee_ids::ResponseData> = self.query(query)?;
+        self.print_log(&response_body);
+        into_data(response_body)?
+            .settings
+            .committee_ids
+            .into_iter()
+            .map(|id| {
+                CommitteeIdDef::from_hex(&id).map_err(|_| ExplorerError::MalformedField {
+                    field: "committeeIds",
+                    value: id,
+                })
+            })
+            .collect()
+    }
+
+    /// Version of the running explorer. Explorers too old to report it yield
+    /// [`ExplorerError::UnsupportedFeature`].
+    pub fn version(&self) -> Result<String, ExplorerError> {
+        let query = Version::build_query(version::Variables);
+        self.print_request(&query);
+        let response_body: Response<version::ResponseData> = self.query(query)?;
+        self.print_log(&response_body);
+        match into_data(response_body) {
+            Ok(data) => Ok(data.version),
+            Err(ExplorerError::GraphQlErrors(errors)) => {
+                self.unless_field_missing("Query", "version", errors, "version")
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Fails with the query `errors`, unless the schema of the explorer has
+    /// no `field` on `type_name`, which is reported as the unsupported
+    /// `feature` instead. Validation errors carry no code to tell them
+    /// apart, so the schema is introspected.
+    fn unless_field_missing<T>(
+        &self,
+        type_name: &str,
+        field: &str,
+        errors: Vec<graphql_client::Error>,
+        feature: &'static str,
+    ) -> Result<T, ExplorerError> {
+        if self.introspect()?.has_field(type_name, field) {
+            Err(ExplorerError::GraphQlErrors(errors))
+        } else {
+            Err(ExplorerError::UnsupportedFeature(feature))
+        }
+    }
+
     pub fn settings(&self) -> Result<Response<settings::ResponseData>, ExplorerError> {
         let query = Settings::build_query(settings::Variables);
         self.print_request(&query);
-        let response = self.client.run(query).map_err(ExplorerError::ClientError)?;
-        let response_body = response.json()?;
+        let response_body = self.query(query)?;
         self.print_log(&response_body);
         Ok(response_body)
     }
 
+    /// Same as [`settings`](Self::settings), wrapped for typed access to the
+    /// fees, the epoch stability depth and the consensus.
+    pub fn settings_response(&self) -> Result<SettingsResponse, ExplorerError> {
+        self.settings().map(SettingsResponse::new)
+    }
+
     pub fn vote_plans(
         &self,
         limit: i64,
     ) -> Result<Response<all_vote_plans::ResponseData>, ExplorerError> {
         let query = AllVotePlans::build_query(all_vote_plans::Variables { first: limit });
         self.print_request(&query);
-        let response = self.client.run(query).map_err(ExplorerError::ClientError)?;
-        let response_body = response.json()?;
+        let response_body = self.query(query)?;
         self.print_log(&response_body);
         Ok(response_body)
     }
 
+    /// The vote plans in `status` among the first `limit` ones, so fewer
+    /// than `limit` plans may be returned even if more exist. The explorer
+    /// has no filter on vote plans, so the status is worked out from the
+    /// vote dates and tallies of each plan against the tip fetched with it.
+    pub fn vote_plans_by_status(
+        &self,
+        status: VoteStatus,
+        limit: i64,
+    ) -> Result<Vec<VotePlanSummary>, ExplorerError> {
+        let tip = into_data(self.vote_plans(limit)?)?.tip;
+        let date = tip.block.date;
+        let tip_date = LibBlockDate {
+            epoch: parse_field("epoch", date.epoch.id)?,
+            slot_id: parse_field("slot", date.slot)?,
+        };
+        let mut vote_plans = Vec::new();
+        for vote_plan in tip
+            .all_vote_plans
+            .edges
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .map(|edge| edge.node)
+        {
+            if VoteStatus::of(&vote_plan, tip_date)? == status {
+                vote_plans.push(vote_plan);
+            }
+        }
+        Ok(vote_plans)
+    }
+
+    /// Looks up a single vote plan. An unknown id is reported as `Ok(None)`,
+    /// any other failure as an error.
+    ///
+    /// The returned data includes `vote_encryption_key`, the bech32 election
+    /// public key of private vote plans, `None` for public ones.
+    pub fn vote_plan(
+        &self,
+        id: Hash,
+    ) -> Result<Option<vote_plan_by_id::VotePlanByIdVotePlan>, ExplorerError> {
+        let query = VotePlanById::build_query(vote_plan_by_id::Variables { id: id.to_string() });
+        self.print_request(&query);
+        let response_body: Response<vote_plan_by_id::ResponseData> = self.query(query)?;
+        self.print_log(&response_body);
+        match into_data(response_body) {
+            Ok(data) => Ok(Some(data.vote_plan)),
+            Err(ExplorerError::GraphQlErrors(errors)) if is_not_found(&errors) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Bech32 election public key votes for the vote plan are encrypted
+    /// with. `None` for public vote plans, and for ids the explorer does not
+    /// know.
+    pub fn vote_plan_encryption_key(&self, id: Hash) -> Result<Option<String>, ExplorerError> {
+        Ok(self
+            .vote_plan(id)?
+            .and_then(|vote_plan| vote_plan.vote_encryption_key))
+    }
+
+    /// Voter count and voting power of every proposal of the vote plan, in
+    /// proposal order, or `None` if there is no such vote plan.
+    pub fn vote_plan_turnout(
+        &self,
+        id: Hash,
+    ) -> Result<Option<Vec<ProposalTurnout>>, ExplorerError> {
+        let query =
+            VotePlanTurnout::build_query(vote_plan_turnout::Variables { id: id.to_string() });
+        self.print_request(&query);
+        let response_body: Response<vote_plan_turnout::ResponseData> = self.query(query)?;
+        self.print_log(&response_body);
+        match into_data(response_body) {
+            Ok(data) => Ok(Some(
+                data.vote_plan
+                    .proposals
+                    .into_iter()
+                    .map(ProposalTurnout::from)
+                    .collect(),
+            )),
+            Err(ExplorerError::GraphQlErrors(errors)) if is_not_found(&errors) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Tally of the proposal at `proposal_index` in the vote plan `vote_plan`.
+    pub fn proposal_tally(
+        &self,
+        vote_plan: Hash,
+        proposal_index: u8,
+    ) -> Result<ProposalTally, ExplorerError> {
+        let vote_plan = match self.vote_plan(vote_plan)? {
+            Some(vote_plan) => vote_plan,
+            None => return Ok(ProposalTally::NoSuchVotePlan),
+        };
+        Ok(vote_plan
+            .proposals
+            .into_iter()
+            .nth(proposal_index as usize)
+            .map(ProposalTally::from)
+            .unwrap_or(ProposalTally::NoSuchProposal))
+    }
+
+    /// Whether transaction `hash` is in a block, and which one.
+    pub fn transaction_status(&self, hash: Hash) -> Result<TxStatus, ExplorerError> {
+        let transaction = match into_data(self.transaction(hash)?) {
+            Ok(data) => data.transaction,
+            Err(ExplorerError::GraphQlErrors(errors)) if is_not_found(&errors) => {
+                return Ok(TxStatus::NotFound)
+            }
+            Err(err) => return Err(err),
+        };
+        let block = match transaction.blocks.into_iter().next() {
+            Some(block) => block,
+            None => return Ok(TxStatus::NotFound),
+        };
+        Ok(TxStatus::InBlock {
+            block: parse_field("id", block.id)?,
+            date: BlockDate::from(LibBlockDate {
+                epoch: parse_field("epoch", block.date.epoch.id)?,
+                slot_id: parse_field("slot", block.date.slot)?,
+            }),
+        })
+    }
+
+    /// Same as [`Explorer::transaction`] for a hash given as text.
+    pub fn transaction_by_hash_str(
+        &self,
+        hash: &str,
+    ) -> Result<Response<transaction_by_id::ResponseData>, ExplorerError> {
+        self.transaction(parse_block_hash(hash)?)
+    }
+
     pub fn transaction(
         &self,
         hash: Hash,
@@ -266,14 +1366,260 @@ impl Explorer {
             id: hash.to_string(),
         });
         self.print_request(&query);
-        let response = self.client.run(query).map_err(ExplorerError::ClientError)?;
-        let response_body: Response<transaction_by_id::ResponseData> = response.json()?;
+        let response_body: Response<transaction_by_id::ResponseData> = self.query(query)?;
         self.print_log(&response_body);
         Ok(response_body)
     }
 
+    /// Looks up every transaction of `hashes` in a single batched request.
+    /// Responses are in the order of `hashes`, and a lookup returning errors
+    /// does not stop the others: its errors are kept in its own response.
+    pub fn transactions(
+        &self,
+        hashes: &[Hash],
+    ) -> Result<Vec<Response<transaction_by_id::ResponseData>>, ExplorerError> {
+        if hashes.is_empty() {
+            return Ok(Vec::new());
+        }
+        let queries = hashes
+            .iter()
+            .map(|hash| {
+                into_value_body(TransactionById::build_query(transaction_by_id::Variables {
+                    id: hash.to_string(),
+                }))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let responses = self.query_batch(&queries)?;
+        if responses.len() != queries.len() {
+            return Err(ExplorerError::BatchSizeMismatch {
+                expected: queries.len(),
+                received: responses.len(),
+            });
+        }
+        responses
+            .into_iter()
+            .map(|response| {
+                let response: Response<transaction_by_id::ResponseData> =
+                    serde_json::from_value(response)?;
+                self.print_log(&response);
+                Ok(response)
+            })
+            .collect()
+    }
+
+    pub fn address_data<S: Into<String>>(
+        &self,
+        bech32_address: S,
+    ) -> Result<address::ResponseData, ExplorerError> {
+        self.address(bech32_address).and_then(into_data)
+    }
+
+    pub fn stake_pools_data(
+        &self,
+        limit: i64,
+    ) -> Result<all_stake_pools::ResponseData, ExplorerError> {
+        self.stake_pools(limit).and_then(into_data)
+    }
+
+    pub fn blocks_data(&self, limit: i64) -> Result<all_blocks::ResponseData, ExplorerError> {
+        self.blocks(limit).and_then(into_data)
+    }
+
+    pub fn last_block_data(&self) -> Result<last_block::ResponseData, ExplorerError> {
+        self.last_block()
+            .and_then(|response| into_data(response.into_response()))
+    }
+
+    pub fn blocks_at_chain_length_data(
+        &self,
+        length: u32,
+    ) -> Result<blocks_by_chain_length::ResponseData, ExplorerError> {
+        self.blocks_at_chain_length(length).and_then(into_data)
+    }
+
+    pub fn epoch_data(
+        &self,
+        epoch_number: u32,
+        limit: i64,
+    ) -> Result<epoch::ResponseData, ExplorerError> {
+        self.epoch(epoch_number, limit).and_then(into_data)
+    }
+
+    pub fn stake_pool_data(
+        &self,
+        id: PoolId,
+        limit: i64,
+    ) -> Result<stake_pool::ResponseData, ExplorerError> {
+        self.stake_pool(id, limit).and_then(into_data)
+    }
+
+    pub fn settings_data(&self) -> Result<settings::ResponseData, ExplorerError> {
+        self.settings().and_then(into_data)
+    }
+
+    pub fn vote_plans_data(
+        &self,
+        limit: i64,
+    ) -> Result<all_vote_plans::ResponseData, ExplorerError> {
+        self.vote_plans(limit).and_then(into_data)
+    }
+
+    pub fn transaction_data(
+        &self,
+        hash: Hash,
+    ) -> Result<transaction_by_id::ResponseData, ExplorerError> {
+        self.transaction(hash).and_then(into_data)
+    }
+
+    pub fn transactions_by_address<S: Into<String>>(
+        &self,
+        bech32_address: S,
+        first: i64,
+    ) -> Result<TransactionsByAddressResponse, ExplorerError> {
+        let query = TransactionsByAddress::build_query(transactions_by_address::Variables {
+            bech32: bech32_address.into(),
+            first,
+        });
+        self.print_request(&query);
+        let response_body = self.query(query)?;
+        self.print_log(&response_body);
+        Ok(TransactionsByAddressResponse::new(response_body))
+    }
+
+    /// Fetches the schema served by the running explorer through the
+    /// standard `__schema` introspection query.
+    pub fn introspect(&self) -> Result<IntrospectionResult, ExplorerError> {
+        let query = QueryBody {
+            variables: (),
+            query: introspection::INTROSPECTION_QUERY,
+            operation_name: introspection::INTROSPECTION_OPERATION_NAME,
+        };
+        self.print_request(&query);
+        let response_body: Response<introspection::IntrospectionData> = self.query(query)?;
+        into_data(response_body).map(Into::into)
+    }
+
+    /// Every new tip announced by the explorer over its WebSocket endpoint,
+    /// to await tip changes instead of polling [`last_block`](Self::last_block).
+    /// The connection is reopened up to [`MAX_RECONNECT_ATTEMPTS`] times in a
+    /// row when it drops, and the stream has to be polled from a tokio
+    /// runtime.
+    pub fn subscribe_tip(
+        &self,
+    ) -> impl futures::Stream<Item = Result<LastBlockResponse, ExplorerError>> {
+        subscription::subscribe_tip(self.client.subscription_url())
+    }
+
+    /// Fetches the settings and the tip block in a single round trip.
+    pub fn settings_and_tip(
+        &self,
+    ) -> Result<(Response<settings::ResponseData>, LastBlockResponse), ExplorerError> {
+        let queries = [
+            into_value_body(Settings::build_query(settings::Variables))?,
+            into_value_body(LastBlock::build_query(last_block::Variables))?,
+        ];
+        let (settings, last_block) =
+            match <[serde_json::Value; 2]>::try_from(self.query_batch(&queries)?) {
+                Ok([settings, last_block]) => (settings, last_block),
+                Err(responses) => {
+                    return Err(ExplorerError::BatchSizeMismatch {
+                        expected: queries.len(),
+                        received: responses.len(),
+                    })
+                }
+            };
+        let settings: Response<settings::ResponseData> = serde_json::from_value(settings)?;
+        let last_block: Response<last_block::ResponseData> = serde_json::from_value(last_block)?;
+        self.print_log(&settings);
+        self.print_log(&last_block);
+        Ok((settings, LastBlockResponse::new(last_block)))
+    }
+
+    /// Hash, chain length and date of the tip together with the epoch
+    /// stability depth, fetched in a single round trip.
+    pub fn tip_summary(&self) -> Result<TipSummary, ExplorerError> {
+        let (settings, last_block) = self.settings_and_tip()?;
+        let settings = into_data(settings)?.settings;
+        let block = into_data(last_block.into_response())?.tip.block;
+        let block_date = LibBlockDate {
+            epoch: parse_field("epoch", block.date.epoch.id)?,
+            slot_id: parse_field("slot", block.date.slot)?,
+        };
+        let epoch_stability_depth = settings.epoch_stability_depth.epoch_stability_depth;
+        Ok(TipSummary {
+            hash: parse_field("block id", block.id)?,
+            chain_length: parse_field("chain length", block.chain_length)?,
+            block_date: BlockDate::from(block_date),
+            epoch_stability_depth: parse_field(
+                "epoch stability depth",
+                epoch_stability_depth.to_string(),
+            )?,
+        })
+    }
+
+    /// Date of the tip block, or the error which prevented fetching it.
+    pub fn try_current_time(&self) -> Result<BlockDate, ExplorerError> {
+        let date = self.last_block_data()?.tip.block.date;
+        let block_date = LibBlockDate {
+            epoch: parse_field("epoch", date.epoch.id)?,
+            slot_id: parse_field("slot", date.slot)?,
+        };
+        Ok(BlockDate::from(block_date))
+    }
+
     pub fn current_time(&self) -> BlockDate {
-        self.last_block().unwrap().block_date()
+        self.try_current_time().unwrap()
+    }
+
+    /// Polls the tip until its chain length is at least `target`.
+    pub fn wait_for_chain_length(
+        &self,
+        target: u32,
+        timeout: Duration,
+    ) -> Result<LastBlockResponse, ExplorerError> {
+        self.wait_for_tip(format!("chain length {}", target), timeout, |tip| {
+            tip.chain_length() >= target
+        })
+    }
+
+    /// Polls the tip until its date is `target` or later.
+    pub fn wait_for_block_date(
+        &self,
+        target: BlockDate,
+        timeout: Duration,
+    ) -> Result<LastBlockResponse, ExplorerError> {
+        self.wait_for_tip(format!("date {}", target), timeout, |tip| {
+            tip.block_date() >= target
+        })
+    }
+
+    /// Failed queries are retried until the timeout, the explorer may be
+    /// catching up with the node.
+    fn wait_for_tip<F: Fn(&LastBlockResponse) -> bool>(
+        &self,
+        target: String,
+        timeout: Duration,
+        reached: F,
+    ) -> Result<LastBlockResponse, ExplorerError> {
+        let attempts = (timeout.as_millis() / BOOTSTRAP_PROBE_INTERVAL.as_millis()).max(1);
+        let mut wait = Wait::new(BOOTSTRAP_PROBE_INTERVAL, attempts as _);
+        let mut last_seen = None;
+        loop {
+            if let Some(tip) = self.last_block().ok().filter(LastBlockResponse::has_data) {
+                if reached(&tip) {
+                    return Ok(tip);
+                }
+                last_seen = Some(Box::new(tip));
+            }
+            if wait.timeout_reached() {
+                return Err(ExplorerError::WaitTimeout {
+                    target,
+                    timeout,
+                    last_seen,
+                });
+            }
+            wait.advance();
+        }
     }
 
     pub fn run<T: Serialize>(
@@ -281,26 +1627,153 @@ impl Explorer {
         query: QueryBody<T>,
     ) -> Result<reqwest::blocking::Response, ExplorerError> {
         self.print_request(&query);
-        let response = self.client.run(query).map_err(ExplorerError::ClientError)?;
+        let response = self.client.run(query).map_err(ExplorerError::from)?;
         self.print_log(&response);
         Ok(response)
     }
 
+    /// Sends an arbitrary query string, e.g. to try out fields before
+    /// generating bindings for them. The response is returned as is, so
+    /// GraphQL errors are found in its `errors` field rather than turned
+    /// into an [`ExplorerError`].
+    pub fn run_raw(
+        &self,
+        query: &str,
+        variables: serde_json::Value,
+    ) -> Result<serde_json::Value, ExplorerError> {
+        if self.print_log {
+            let logged = log::variables(&variables, self.logged_variable_len());
+            log::query(
+                client::RAW_OPERATION_NAME,
+                query,
+                Some(&logged),
+                &self.uri(),
+            );
+        }
+        let response = self
+            .client
+            .run_raw(query, &variables)
+            .map_err(ExplorerError::from)?;
+        self.print_log(&response);
+        Ok(response)
+    }
+
+    /// Sends `query` and decodes its response, saving it or reading it
+    /// instead from the fixtures, if any. Replayed responses are not part of
+    /// the metrics.
+    fn query<T: Serialize, R: DeserializeOwned>(
+        &self,
+        query: QueryBody<T>,
+    ) -> Result<R, ExplorerError> {
+        let query = into_value_body(query)?;
+        match &self.fixtures {
+            Some(Fixtures::Replay(dir)) => decode_body(&fixtures::read_fixture(
+                dir,
+                &fixtures::fixture_name(&query)?,
+            )?),
+            Some(Fixtures::Capture(dir)) => {
+                let (body, response) = self.client.timed(|| {
+                    let body = self.transport().send(&query)?;
+                    let response = decode_body(&body);
+                    Ok::<_, ExplorerError>((body, response))
+                })?;
+                fixtures::write_fixture(dir, &fixtures::fixture_name(&query)?, &body)?;
+                response
+            }
+            None => self
+                .client
+                .timed(|| decode_body(&self.transport().send(&query)?)),
+        }
+    }
+
+    /// Same as [`Explorer::query`] for queries sent in a single request, each
+    /// saved to or read from its own fixture.
+    fn query_batch(
+        &self,
+        queries: &[QueryBody<serde_json::Value>],
+    ) -> Result<Vec<serde_json::Value>, ExplorerError> {
+        match &self.fixtures {
+            Some(Fixtures::Replay(dir)) => queries
+                .iter()
+                .map(|query| {
+                    decode_body(&fixtures::read_fixture(
+                        dir,
+                        &fixtures::fixture_name(query)?,
+                    )?)
+                })
+                .collect(),
+            Some(Fixtures::Capture(dir)) => {
+                let responses = self.send_batch(queries)?;
+                for (query, response) in queries.iter().zip(&responses) {
+                    let body = serde_json::to_string(response)?;
+                    fixtures::write_fixture(dir, &fixtures::fixture_name(query)?, &body)?;
+                }
+                Ok(responses)
+            }
+            None => self.send_batch(queries),
+        }
+    }
+
+    /// Queries are sent one by one through a transport other than the
+    /// client, which has no notion of batches.
+    fn send_batch(
+        &self,
+        queries: &[QueryBody<serde_json::Value>],
+    ) -> Result<Vec<serde_json::Value>, ExplorerError> {
+        match &self.transport {
+            Some(transport) => queries
+                .iter()
+                .map(|query| decode_body(&transport.send(query)?))
+                .collect(),
+            None => self.client.run_batch(queries).map_err(ExplorerError::from),
+        }
+    }
+
+    fn transport(&self) -> &dyn GraphQlTransport {
+        match &self.transport {
+            Some(transport) => transport.as_ref(),
+            None => &self.client,
+        }
+    }
+
     fn print_log<T: std::fmt::Debug>(&self, response: &T) {
         if self.print_log {
-            println!("Response: {:?}", &response);
+            log::response(response);
         }
     }
 }
 
-#[allow(dead_code)]
-pub fn compare_schema<P: AsRef<Path>>(actual_schema_path: P) {
-    let expected_schema_path =
-        PathBuf::from_str("./jormungandr-automation/resources/explorer/graphql/schema.graphql")
-            .unwrap();
-
-    if !file::have_the_same_content(actual_schema_path.as_ref(), &expected_schema_path) {
-        file::copy_file(actual_schema_path.as_ref(), &expected_schema_path, true);
-        println!("discrepancies detected, already replaced file with new content. Please commit to update schema");
+/// Polls the tip of every explorer until they all report the same block,
+/// failing with [`ExplorerError::TipsDiverged`] after `timeout`. Explorers
+/// failing to answer are retried like the others.
+pub fn assert_tips_converged(
+    explorers: &[Explorer],
+    timeout: Duration,
+) -> Result<(), ExplorerError> {
+    let attempts = (timeout.as_millis() / BOOTSTRAP_PROBE_INTERVAL.as_millis()).max(1);
+    let mut wait = Wait::new(BOOTSTRAP_PROBE_INTERVAL, attempts as _);
+    loop {
+        let tips: Vec<_> = explorers
+            .iter()
+            .map(|explorer| {
+                let tip = explorer
+                    .last_block()
+                    .ok()
+                    .filter(LastBlockResponse::has_data);
+                (explorer.uri(), tip.map(Box::new))
+            })
+            .collect();
+        let first = tips.first().and_then(|(_, tip)| tip.as_ref());
+        let converged = tips.iter().all(|(_, tip)| match (tip, first) {
+            (Some(tip), Some(first)) => tip.block().id == first.block().id,
+            _ => false,
+        });
+        if converged {
+            return Ok(());
+        }
+        if wait.timeout_reached() {
+            return Err(ExplorerError::TipsDiverged { timeout, tips });
+        }
+        wait.advance();
     }
 }
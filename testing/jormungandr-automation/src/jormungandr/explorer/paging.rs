@@ -0,0 +1,70 @@
+use super::ExplorerError;
+
+/// One page of a GraphQL connection.
+#[derive(Debug)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Cursor to pass as `after` to fetch the next page.
+    pub end_cursor: Option<String>,
+    pub has_next_page: bool,
+}
+
+/// Walks a connection page by page, yielding its items one at a time.
+///
+/// Only one page is held in memory at any time. A failed page fetch is
+/// yielded as an `Err` item and ends the iteration.
+pub(super) struct PagedIter<T, F>
+where
+    F: FnMut(Option<String>) -> Result<Page<T>, ExplorerError>,
+{
+    fetch: F,
+    cursor: Option<String>,
+    buffer: std::vec::IntoIter<T>,
+    finished: bool,
+}
+
+impl<T, F> PagedIter<T, F>
+where
+    F: FnMut(Option<String>) -> Result<Page<T>, ExplorerError>,
+{
+    pub(super) fn new(fetch: F) -> Self {
+        Self {
+            fetch,
+            cursor: None,
+            buffer: Vec::new().into_iter(),
+            finished: false,
+        }
+    }
+}
+
+impl<T, F> Iterator for PagedIter<T, F>
+where
+    F: FnMut(Option<String>) -> Result<Page<T>, ExplorerError>,
+{
+    type Item = Result<T, ExplorerError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.buffer.next() {
+                return Some(Ok(item));
+            }
+            if self.finished {
+                return None;
+            }
+            match (self.fetch)(self.cursor.take()) {
+                Ok(page) => {
+                    // an empty page can't move the cursor forward, stop there
+                    // rather than asking for the same page forever
+                    self.finished =
+                        !page.has_next_page || page.end_cursor.is_none() || page.items.is_empty();
+                    self.cursor = page.end_cursor;
+                    self.buffer = page.items.into_iter();
+                }
+                Err(err) => {
+                    self.finished = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
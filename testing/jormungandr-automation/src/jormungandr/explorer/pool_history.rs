@@ -0,0 +1,122 @@
+use super::{
+    data::pool_certificates::{
+        PoolCertificatesTipBlocksEdgesNode,
+        PoolCertificatesTipBlocksEdgesNodeTransactionsEdgesNodeCertificate as Certificate,
+    },
+    parse_field, BlockDate, ExplorerError,
+};
+use chain_impl_mockchain::block::BlockDate as LibBlockDate;
+use jormungandr_lib::crypto::hash::Hash;
+
+/// Certificate affecting a stake pool. Times are the offsets in seconds
+/// reported by the explorer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PoolCertificate {
+    Registration { start_validity: String },
+    Update { start_validity: String },
+    Retirement { retirement_time: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolHistoryEntry {
+    pub certificate: PoolCertificate,
+    pub transaction: Hash,
+    pub block_date: BlockDate,
+}
+
+/// Where a pool stands after its last certificate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoolLifecycle {
+    /// Registered and never updated.
+    Registered,
+    Updated {
+        updates: usize,
+    },
+    Retired,
+}
+
+/// Certificates of a stake pool, in chain order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StakePoolHistory {
+    entries: Vec<PoolHistoryEntry>,
+}
+
+impl StakePoolHistory {
+    pub(super) fn new(entries: Vec<PoolHistoryEntry>) -> Self {
+        Self { entries }
+    }
+
+    pub fn entries(&self) -> &[PoolHistoryEntry] {
+        &self.entries
+    }
+
+    pub fn registration(&self) -> Option<&PoolHistoryEntry> {
+        self.entries
+            .iter()
+            .find(|entry| matches!(entry.certificate, PoolCertificate::Registration { .. }))
+    }
+
+    pub fn updates(&self) -> impl Iterator<Item = &PoolHistoryEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| matches!(entry.certificate, PoolCertificate::Update { .. }))
+    }
+
+    /// The retirement certificate, if it is the last one of the pool.
+    pub fn retirement(&self) -> Option<&PoolHistoryEntry> {
+        self.entries
+            .last()
+            .filter(|entry| matches!(entry.certificate, PoolCertificate::Retirement { .. }))
+    }
+
+    pub fn lifecycle(&self) -> PoolLifecycle {
+        if self.retirement().is_some() {
+            return PoolLifecycle::Retired;
+        }
+        match self.updates().count() {
+            0 => PoolLifecycle::Registered,
+            updates => PoolLifecycle::Updated { updates },
+        }
+    }
+}
+
+/// Certificates of the pool `pool_id` found in `block`.
+pub(super) fn pool_entries(
+    block: PoolCertificatesTipBlocksEdgesNode,
+    pool_id: &str,
+) -> Result<Vec<PoolHistoryEntry>, ExplorerError> {
+    let block_date = BlockDate::from(LibBlockDate {
+        epoch: parse_field("epoch", block.date.epoch.id)?,
+        slot_id: parse_field("slot", block.date.slot)?,
+    });
+    let mut entries = Vec::new();
+    for transaction in block.transactions.edges.into_iter().flatten().flatten() {
+        let transaction = transaction.node;
+        let certificate = match transaction.certificate {
+            Some(Certificate::PoolRegistration(registration))
+                if registration.pool.id == pool_id =>
+            {
+                PoolCertificate::Registration {
+                    start_validity: registration.start_validity,
+                }
+            }
+            Some(Certificate::PoolUpdate(update)) if update.pool_id == pool_id => {
+                PoolCertificate::Update {
+                    start_validity: update.start_validity,
+                }
+            }
+            Some(Certificate::PoolRetirement(retirement)) if retirement.pool_id == pool_id => {
+                PoolCertificate::Retirement {
+                    retirement_time: retirement.retirement_time,
+                }
+            }
+            _ => continue,
+        };
+        entries.push(PoolHistoryEntry {
+            certificate,
+            transaction: parse_field("transaction id", transaction.id)?,
+            block_date,
+        });
+    }
+    Ok(entries)
+}
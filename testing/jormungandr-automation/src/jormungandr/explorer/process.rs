@@ -0,0 +1,418 @@
+use super::ExplorerError;
+use crate::testing::configuration::explorer_app_path;
+use std::{
+    collections::VecDeque,
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufReader, Read, Write},
+    path::{Path, PathBuf},
+    process::{Child, Command, ExitStatus, Stdio},
+    sync::{Arc, Mutex, PoisonError},
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+pub(super) const DEFAULT_SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(5);
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const LOG_FILE_MAX_SIZE: u64 = 64 * 1024 * 1024;
+/// Lines of output kept in memory by default, see [`ExplorerProcess::spawn`].
+pub const DEFAULT_LOG_BUFFER_LINES: usize = 1000;
+/// Largest tail of the output written to `logs_dir` on panic by default, in
+/// bytes, see [`ExplorerProcess::set_panic_log_max_size`].
+pub const DEFAULT_PANIC_LOG_MAX_SIZE: usize = 1024 * 1024;
+/// Environment variable overriding the path of the explorer executable.
+pub const EXPLORER_APP_ENV: &str = "EXPLORER_APP";
+
+/// Flags set by [`ExplorerProcess::spawn`] or the builder, which callers may
+/// not pass again through the extra arguments.
+pub(super) const RESERVED_ARGS: &[&str] =
+    &["--node", "--binding-address", "--log-output", "--log-level"];
+
+pub(super) struct ExplorerProcess {
+    handler: Mutex<Option<Child>>,
+    args: Vec<String>,
+    logs_dir: Option<PathBuf>,
+    log_file: Mutex<Option<PathBuf>>,
+    log_threads: Mutex<Vec<JoinHandle<()>>>,
+    output: Arc<CapturedOutput>,
+    shutdown_grace_period: Duration,
+    panic_logs: Mutex<PanicLogs>,
+}
+
+/// What is written to `logs_dir` when dropped during a panic.
+struct PanicLogs {
+    persist: bool,
+    max_size: usize,
+}
+
+/// Where the reader threads put the explorer output.
+struct CapturedOutput {
+    /// Both streams, in the order lines were read.
+    recent: Mutex<LogBuffer>,
+    stderr: Mutex<LogBuffer>,
+    file: Mutex<Option<RotatingLogFile>>,
+}
+
+/// The last `capacity` lines of a stream.
+struct LogBuffer {
+    lines: VecDeque<String>,
+    capacity: usize,
+}
+
+impl LogBuffer {
+    fn new(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::with_capacity(capacity.min(DEFAULT_LOG_BUFFER_LINES)),
+            capacity,
+        }
+    }
+
+    fn push(&mut self, line: String) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+    }
+
+    fn tail(&self, lines: usize) -> Vec<String> {
+        let skip = self.lines.len().saturating_sub(lines);
+        self.lines.iter().skip(skip).cloned().collect()
+    }
+}
+
+/// The explorer executable named by [`EXPLORER_APP_ENV`], or the one next to
+/// the test binaries when the variable is unset or empty.
+fn resolve_explorer_app() -> PathBuf {
+    match std::env::var_os(EXPLORER_APP_ENV) {
+        Some(path) if !path.is_empty() => PathBuf::from(path),
+        _ => explorer_app_path(),
+    }
+}
+
+impl ExplorerProcess {
+    /// Starts the explorer with its stdout and stderr continuously drained
+    /// by reader threads, so that a chatty explorer never blocks on a full
+    /// pipe. The last `log_buffer_lines` lines are kept in memory.
+    pub(super) fn spawn(
+        node_address: &str,
+        explorer_listen_address: &str,
+        logs_dir: Option<PathBuf>,
+        shutdown_grace_period: Duration,
+        log_buffer_lines: usize,
+        extra_args: &[String],
+    ) -> Result<ExplorerProcess, ExplorerError> {
+        let path = resolve_explorer_app();
+        if !path.is_file() {
+            return Err(ExplorerError::BinaryNotFound(path));
+        }
+
+        let mut args: Vec<String> = vec![
+            "--node".to_string(),
+            node_address.to_string(),
+            "--binding-address".to_string(),
+            explorer_listen_address.to_string(),
+            "--log-output".to_string(),
+            "stdout".to_string(),
+        ];
+        args.extend_from_slice(extra_args);
+
+        let mut handler = Command::new(&path)
+            .args(&args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|source| ExplorerError::SpawnFailed {
+                program: path,
+                source,
+            })?;
+
+        let output = Arc::new(CapturedOutput {
+            recent: Mutex::new(LogBuffer::new(log_buffer_lines)),
+            stderr: Mutex::new(LogBuffer::new(log_buffer_lines)),
+            file: Mutex::new(None),
+        });
+        let log_threads = vec![
+            stream_lines(handler.stdout.take().unwrap(), Arc::clone(&output), false),
+            stream_lines(handler.stderr.take().unwrap(), Arc::clone(&output), true),
+        ];
+
+        Ok(ExplorerProcess {
+            handler: Mutex::new(Some(handler)),
+            args,
+            logs_dir,
+            log_file: Mutex::new(None),
+            log_threads: Mutex::new(log_threads),
+            output,
+            shutdown_grace_period,
+            panic_logs: Mutex::new(PanicLogs {
+                persist: true,
+                max_size: DEFAULT_PANIC_LOG_MAX_SIZE,
+            }),
+        })
+    }
+
+    /// Whether the output kept in memory is written to `logs_dir` when the
+    /// process is dropped during a panic, on by default.
+    pub(super) fn set_persist_logs_on_panic(&self, persist: bool) {
+        self.panic_logs.lock().unwrap().persist = persist;
+    }
+
+    /// Only the last lines of output fitting in `max_size` bytes are written
+    /// to `logs_dir` on panic.
+    pub(super) fn set_panic_log_max_size(&self, max_size: usize) {
+        self.panic_logs.lock().unwrap().max_size = max_size;
+    }
+
+    /// Starts copying the explorer stdout and stderr to `path` as they are
+    /// produced. Once the file grows past 64 MiB it is moved aside to
+    /// `<path>.1` and a fresh one is started.
+    pub(super) fn enable_file_logging(&self, path: &Path) -> Result<(), ExplorerError> {
+        let mut file = self.output.file.lock().unwrap();
+        if file.is_some() {
+            return Err(ExplorerError::FileLoggingAlreadyEnabled);
+        }
+
+        let mut log_file = RotatingLogFile::open(path.to_path_buf(), LOG_FILE_MAX_SIZE)?;
+        log_file.write_line(format!("explorer args: {}\n", self.args.join(" ")).as_bytes())?;
+        *file = Some(log_file);
+
+        *self.log_file.lock().unwrap() = Some(path.to_path_buf());
+        Ok(())
+    }
+
+    /// Last `lines` lines of output, stdout and stderr interleaved as they
+    /// were read.
+    pub(super) fn recent_logs(&self, lines: usize) -> Vec<String> {
+        self.output.recent.lock().unwrap().tail(lines)
+    }
+
+    /// Exit status of the process if it has exited, without waiting for it.
+    pub(super) fn status(&self) -> Option<ExitStatus> {
+        self.handler
+            .lock()
+            .unwrap()
+            .as_mut()
+            .and_then(|child| child.try_wait().ok().flatten())
+    }
+
+    /// Exit status and last `lines` lines of stderr if the process has
+    /// exited. The reader threads are joined first, so the tail holds
+    /// everything the process wrote before exiting.
+    pub(super) fn exited(&self, lines: usize) -> Option<(ExitStatus, String)> {
+        let status = self.status()?;
+        for log_thread in self.log_threads.lock().unwrap().drain(..) {
+            let _ = log_thread.join();
+        }
+        Some((status, self.stderr_tail(lines)))
+    }
+
+    /// Last `lines` lines written to stderr, within the lines kept in memory.
+    pub(super) fn stderr_tail(&self, lines: usize) -> String {
+        self.output.stderr.lock().unwrap().tail(lines).join("\n")
+    }
+}
+
+impl Drop for ExplorerProcess {
+    fn drop(&mut self) {
+        let handler = self
+            .handler
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        let mut handler = match handler {
+            Some(handler) => handler,
+            None => return,
+        };
+        shutdown(&mut handler, self.shutdown_grace_period);
+        let _ = handler.wait();
+
+        // the readers run until the pipes are closed, which happened when the
+        // process exited
+        let log_threads = self
+            .log_threads
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+        for log_thread in log_threads.drain(..) {
+            let _ = log_thread.join();
+        }
+
+        let panic_logs = self
+            .panic_logs
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+        if std::thread::panicking() {
+            let log_file = self
+                .log_file
+                .get_mut()
+                .unwrap_or_else(PoisonError::into_inner);
+            if let Some(log_file) = log_file {
+                println!("explorer logs were streamed to: {}", log_file.display());
+            } else if let Some(logs_dir) = self.logs_dir.as_ref().filter(|_| panic_logs.persist) {
+                println!(
+                    "persisting explorer logs after panic: {}",
+                    logs_dir.display()
+                );
+
+                // `output` is shared with the readers, which are done by now
+                let recent = self
+                    .output
+                    .recent
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner);
+                let mut size = 0;
+                let tail = recent
+                    .lines
+                    .iter()
+                    .rev()
+                    .take_while(|line| {
+                        size += line.len() + 1;
+                        size <= panic_logs.max_size
+                    })
+                    .count();
+                let mut content = format!("explorer args: {}\n", self.args.join(" "));
+                if tail < recent.lines.len() {
+                    content.push_str(&format!(
+                        "[{} earlier lines dropped]\n",
+                        recent.lines.len() - tail
+                    ));
+                }
+                for line in recent.lines.iter().skip(recent.lines.len() - tail) {
+                    content.push_str(line);
+                    content.push('\n');
+                }
+
+                std::fs::write(logs_dir.join("explorer.log"), content)
+                    .unwrap_or_else(|e| eprint!("Could not write explorer logs to disk: {}", e));
+            }
+        }
+    }
+}
+
+struct RotatingLogFile {
+    path: PathBuf,
+    file: File,
+    written: u64,
+    max_size: u64,
+}
+
+impl RotatingLogFile {
+    fn open(path: PathBuf, max_size: u64) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            path,
+            file,
+            written,
+            max_size,
+        })
+    }
+
+    fn write_line(&mut self, line: &[u8]) -> io::Result<()> {
+        if self.written > 0 && self.written + line.len() as u64 > self.max_size {
+            self.rotate()?;
+        }
+        self.file.write_all(line)?;
+        self.written += line.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        let mut rotated = self.path.clone().into_os_string();
+        rotated.push(".1");
+        std::fs::rename(&self.path, rotated)?;
+        self.file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.path)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+fn stream_lines<R>(source: R, output: Arc<CapturedOutput>, is_stderr: bool) -> JoinHandle<()>
+where
+    R: Read + Send + 'static,
+{
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(source);
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            match reader.read_until(b'\n', &mut line) {
+                Ok(0) | Err(_) => break,
+                // keep draining the pipe even if the file can't be written,
+                // otherwise the explorer would eventually block on its output
+                Ok(_) => {
+                    if let Ok(mut file) = output.file.lock() {
+                        if let Some(file) = file.as_mut() {
+                            let _ = file.write_line(&line);
+                        }
+                    }
+                    let text = String::from_utf8_lossy(&line).trim_end().to_string();
+                    if is_stderr {
+                        if let Ok(mut stderr) = output.stderr.lock() {
+                            stderr.push(text.clone());
+                        }
+                    }
+                    if let Ok(mut recent) = output.recent.lock() {
+                        recent.push(text);
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Asks the process to terminate and gives it `grace_period` to exit on its
+/// own before killing it.
+fn shutdown(handler: &mut Child, grace_period: Duration) {
+    if terminate(handler) {
+        let started = Instant::now();
+        while started.elapsed() < grace_period {
+            match handler.try_wait() {
+                Ok(Some(_)) => return,
+                Ok(None) => std::thread::sleep(SHUTDOWN_POLL_INTERVAL),
+                Err(_) => break,
+            }
+        }
+    }
+    let _ = handler.kill();
+}
+
+#[cfg(unix)]
+fn terminate(handler: &Child) -> bool {
+    use nix::{
+        sys::signal::{kill, Signal},
+        unistd::Pid,
+    };
+
+    kill(Pid::from_raw(handler.id() as i32), Signal::SIGTERM).is_ok()
+}
+
+// there is no SIGTERM equivalent we can send, kill right away
+#[cfg(not(unix))]
+fn terminate(_handler: &Child) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_buffer_keeps_the_last_lines() {
+        let mut buffer = LogBuffer::new(2);
+        for line in &["a", "b", "c"] {
+            buffer.push(line.to_string());
+        }
+        assert_eq!(buffer.tail(usize::MAX), vec!["b", "c"]);
+        assert_eq!(buffer.tail(1), vec!["c"]);
+
+        let mut disabled = LogBuffer::new(0);
+        disabled.push("a".to_string());
+        assert!(disabled.tail(usize::MAX).is_empty());
+    }
+}
@@ -0,0 +1,59 @@
+use super::{data::epoch_rewards, parse_field, ExplorerError};
+use jormungandr_lib::crypto::hash::Hash;
+
+/// What the explorer knows of the rewards of an epoch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EpochRewardsOutcome {
+    Distributed(EpochRewards),
+    /// The epoch is not over, rewards are distributed once the chain moves
+    /// to the next one.
+    NotYetDistributed,
+}
+
+/// Rewards distributed at the end of an epoch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpochRewards {
+    /// Set when the epoch ended before rewards began, in which case all the
+    /// amounts are zero rather than missing.
+    pub before_rewards_start: bool,
+    pub total: u64,
+    pub treasury: u64,
+    pub pools: Vec<PoolReward>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolReward {
+    pub pool_id: Hash,
+    pub reward: u64,
+}
+
+impl EpochRewards {
+    pub(super) fn new(
+        rewards: epoch_rewards::EpochRewardsEpochRewards,
+    ) -> Result<Self, ExplorerError> {
+        let pools = rewards
+            .pools
+            .into_iter()
+            .map(|pool| {
+                Ok(PoolReward {
+                    pool_id: parse_field("pool id", pool.pool.id)?,
+                    reward: parse_field("pool reward", pool.reward)?,
+                })
+            })
+            .collect::<Result<_, ExplorerError>>()?;
+        Ok(Self {
+            before_rewards_start: rewards.before_rewards_start,
+            total: parse_field("total reward", rewards.total)?,
+            treasury: parse_field("treasury reward", rewards.treasury)?,
+            pools,
+        })
+    }
+
+    /// Reward of `pool_id`, zero if the pool got nothing.
+    pub fn pool_reward(&self, pool_id: &Hash) -> u64 {
+        self.pools
+            .iter()
+            .find(|pool| &pool.pool_id == pool_id)
+            .map_or(0, |pool| pool.reward)
+    }
+}
@@ -0,0 +1,112 @@
+use super::ExplorerError;
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+const EXPECTED_SCHEMA_PATH: &str =
+    "./jormungandr-automation/resources/explorer/graphql/schema.graphql";
+
+/// Environment variable making [`SchemaDiff::update`] fail on drift instead
+/// of overwriting the committed schema, as wanted in CI. Any value but an
+/// empty one, `0` or `false` turns it on.
+pub const SCHEMA_CHECK_ONLY_ENV: &str = "SCHEMA_CHECK_ONLY";
+
+/// Outcome of comparing a freshly generated explorer schema with the one
+/// committed in the repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaDiff {
+    actual: PathBuf,
+    expected: PathBuf,
+    changed_lines: Vec<Range<usize>>,
+}
+
+impl SchemaDiff {
+    pub fn is_up_to_date(&self) -> bool {
+        self.changed_lines.is_empty()
+    }
+
+    /// 1-based, end-exclusive ranges of the lines which differ between the
+    /// two schemas.
+    pub fn changed_lines(&self) -> &[Range<usize>] {
+        &self.changed_lines
+    }
+
+    /// Overwrites the committed schema with the generated one.
+    pub fn apply(&self) -> Result<(), ExplorerError> {
+        std::fs::copy(&self.actual, &self.expected)?;
+        Ok(())
+    }
+
+    /// Does nothing if the committed schema is up to date. Otherwise fails
+    /// with [`ExplorerError::SchemaOutOfDate`] when `check_only` is set,
+    /// leaving the committed schema untouched, or [applies](Self::apply)
+    /// the generated one. Returns whether the committed schema was rewritten.
+    pub fn update(&self, check_only: bool) -> Result<bool, ExplorerError> {
+        if self.is_up_to_date() {
+            return Ok(false);
+        }
+        if check_only {
+            return Err(ExplorerError::SchemaOutOfDate {
+                expected: self.expected.clone(),
+                changed_lines: self.changed_lines.clone(),
+            });
+        }
+        self.apply().map(|()| true)
+    }
+}
+
+/// Whether [`SCHEMA_CHECK_ONLY_ENV`] asks for drift to fail.
+pub fn schema_check_only() -> bool {
+    match std::env::var(SCHEMA_CHECK_ONLY_ENV) {
+        Ok(value) => !matches!(value.as_str(), "" | "0" | "false"),
+        Err(_) => false,
+    }
+}
+
+pub fn compare_schema<P: AsRef<Path>>(actual_schema_path: P) -> Result<SchemaDiff, ExplorerError> {
+    let actual = actual_schema_path.as_ref().to_path_buf();
+    let expected = PathBuf::from(EXPECTED_SCHEMA_PATH);
+
+    let changed_lines = changed_lines(
+        &std::fs::read_to_string(&actual)?,
+        &std::fs::read_to_string(&expected)?,
+    );
+
+    Ok(SchemaDiff {
+        actual,
+        expected,
+        changed_lines,
+    })
+}
+
+fn changed_lines(actual: &str, expected: &str) -> Vec<Range<usize>> {
+    let actual: Vec<&str> = actual.lines().collect();
+    let expected: Vec<&str> = expected.lines().collect();
+
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for line in 0..actual.len().max(expected.len()) {
+        if actual.get(line) == expected.get(line) {
+            continue;
+        }
+        let line_number = line + 1;
+        match ranges.last_mut() {
+            Some(range) if range.end == line_number => range.end += 1,
+            _ => ranges.push(line_number..line_number + 1),
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::changed_lines;
+
+    #[test]
+    pub fn changed_lines_are_grouped_into_ranges() {
+        assert!(changed_lines("a\nb\nc", "a\nb\nc").is_empty());
+        assert_eq!(changed_lines("a\nx\ny\nd", "a\nb\nc\nd"), vec![2..4]);
+        assert_eq!(changed_lines("a\nb", "a\nb\nc\nd"), vec![3..5]);
+        assert_eq!(changed_lines("x\nb\ny", "a\nb\nc"), vec![1..2, 3..4]);
+    }
+}
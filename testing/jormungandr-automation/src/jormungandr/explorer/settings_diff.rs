@@ -0,0 +1,65 @@
+use super::data::settings;
+use jormungandr_lib::interfaces::SettingsDto;
+
+/// A single setting whose value reported by the explorer differs from the
+/// one the node was configured with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettingsMismatch {
+    pub field: &'static str,
+    pub expected: String,
+    pub actual: String,
+}
+
+/// Outcome of comparing the settings reported by the explorer with the node
+/// settings.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SettingsDiff {
+    mismatches: Vec<SettingsMismatch>,
+}
+
+impl SettingsDiff {
+    pub fn is_empty(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    pub fn mismatches(&self) -> &[SettingsMismatch] {
+        &self.mismatches
+    }
+
+    fn check<E: ToString, A: ToString>(&mut self, field: &'static str, expected: E, actual: A) {
+        let expected = expected.to_string();
+        let actual = actual.to_string();
+        if expected != actual {
+            self.mismatches.push(SettingsMismatch {
+                field,
+                expected,
+                actual,
+            });
+        }
+    }
+}
+
+/// Compares every setting exposed by the explorer `settings` query with the
+/// node settings. Settings the explorer does not expose are not compared.
+pub fn diff_settings(actual: &settings::ResponseData, expected: &SettingsDto) -> SettingsDiff {
+    let mut diff = SettingsDiff::default();
+    let fees = &actual.settings.fees;
+
+    diff.check("fees.constant", expected.fees.constant, fees.constant);
+    diff.check(
+        "fees.coefficient",
+        expected.fees.coefficient,
+        fees.coefficient,
+    );
+    diff.check(
+        "fees.certificate",
+        expected.fees.certificate,
+        fees.certificate,
+    );
+    diff.check(
+        "epoch_stability_depth",
+        expected.epoch_stability_depth,
+        actual.settings.epoch_stability_depth.epoch_stability_depth,
+    );
+    diff
+}
@@ -0,0 +1,164 @@
+use super::{data::last_block, ExplorerError, LastBlockResponse};
+use futures::{stream, SinkExt, Stream, StreamExt};
+use graphql_client::Response;
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{client::IntoClientRequest, http::HeaderValue, Message},
+    MaybeTlsStream, WebSocketStream,
+};
+
+/// Subprotocol of the `subscriptions-transport-ws` library, which is the
+/// default of the explorer subscription endpoint.
+const PROTOCOL: &str = "graphql-ws";
+const SUBSCRIPTION_ID: &str = "1";
+/// Connection attempts made in a row before the stream gives up.
+pub const MAX_RECONNECT_ATTEMPTS: u32 = 5;
+const RECONNECT_DELAY: Duration = Duration::from_millis(500);
+
+// same selection as `lastblock.graphql`, so the events decode as its response
+const TIP_SUBSCRIPTION: &str = "subscription TipSubscription { tip { block { id chainLength \
+    date { epoch { id } slot } leader { __typename ... on Pool { id } } } } }";
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage {
+    ConnectionAck,
+    #[serde(rename = "ka")]
+    KeepAlive,
+    ConnectionError {
+        payload: serde_json::Value,
+    },
+    Data {
+        payload: Response<last_block::ResponseData>,
+    },
+    Error {
+        payload: serde_json::Value,
+    },
+    Complete,
+}
+
+enum Failure {
+    /// The connection was lost, a new one may succeed.
+    Dropped(ExplorerError),
+    /// The explorer refused the subscription, retrying will not help.
+    Fatal(ExplorerError),
+}
+
+struct State {
+    url: String,
+    socket: Option<Socket>,
+    failures: u32,
+}
+
+impl State {
+    /// Waits before the next connection attempt, or returns `false` once
+    /// too many attempts in a row failed.
+    async fn backoff(&mut self) -> bool {
+        self.socket = None;
+        self.failures += 1;
+        if self.failures > MAX_RECONNECT_ATTEMPTS {
+            return false;
+        }
+        tokio::time::sleep(RECONNECT_DELAY * self.failures).await;
+        true
+    }
+}
+
+/// Streams the tip every time the explorer reports a new one. Dropped
+/// connections are reopened transparently, the tips announced in between
+/// are missed. The stream ends after yielding the error which stopped it.
+pub(super) fn subscribe_tip(
+    url: String,
+) -> impl Stream<Item = Result<LastBlockResponse, ExplorerError>> {
+    let state = State {
+        url,
+        socket: None,
+        failures: 0,
+    };
+    stream::unfold(Some(state), |state| async move {
+        let mut state = state?;
+        loop {
+            if state.socket.is_none() {
+                match connect(&state.url).await {
+                    Ok(socket) => state.socket = Some(socket),
+                    Err(error) => {
+                        if state.backoff().await {
+                            continue;
+                        }
+                        return Some((Err(error), None));
+                    }
+                }
+            }
+            let socket = state.socket.as_mut().unwrap();
+            match receive(socket).await {
+                Ok(Some(tip)) => {
+                    state.failures = 0;
+                    return Some((Ok(tip), Some(state)));
+                }
+                Ok(None) => {}
+                Err(Failure::Dropped(error)) => {
+                    if !state.backoff().await {
+                        return Some((Err(error), None));
+                    }
+                }
+                Err(Failure::Fatal(error)) => return Some((Err(error), None)),
+            }
+        }
+    })
+}
+
+async fn connect(url: &str) -> Result<Socket, ExplorerError> {
+    let mut request = url.into_client_request()?;
+    request
+        .headers_mut()
+        .insert("Sec-WebSocket-Protocol", HeaderValue::from_static(PROTOCOL));
+    let (mut socket, _) = connect_async(request).await?;
+    let init = json!({ "type": "connection_init", "payload": {} });
+    socket.send(Message::Text(init.to_string())).await?;
+    let start = json!({
+        "id": SUBSCRIPTION_ID,
+        "type": "start",
+        "payload": { "query": TIP_SUBSCRIPTION },
+    });
+    socket.send(Message::Text(start.to_string())).await?;
+    Ok(socket)
+}
+
+/// Reads the next message, `None` for the ones which carry no tip.
+async fn receive(socket: &mut Socket) -> Result<Option<LastBlockResponse>, Failure> {
+    let text = match socket.next().await {
+        Some(Ok(Message::Text(text))) => text,
+        Some(Ok(Message::Close(_))) | None => {
+            return Err(Failure::Dropped(ExplorerError::SubscriptionClosed))
+        }
+        // pings are answered by tungstenite itself
+        Some(Ok(_)) => return Ok(None),
+        Some(Err(error)) => return Err(Failure::Dropped(error.into())),
+    };
+    let message = serde_json::from_str(&text).map_err(|error| Failure::Fatal(error.into()))?;
+    match message {
+        ServerMessage::ConnectionAck | ServerMessage::KeepAlive => Ok(None),
+        ServerMessage::Data { payload } => {
+            let has_errors = payload
+                .errors
+                .as_ref()
+                .map_or(false, |errors| !errors.is_empty());
+            if payload.data.is_none() || has_errors {
+                return Err(Failure::Fatal(ExplorerError::GraphQlErrors(
+                    payload.errors.unwrap_or_default(),
+                )));
+            }
+            Ok(Some(LastBlockResponse::new(payload)))
+        }
+        ServerMessage::ConnectionError { payload } | ServerMessage::Error { payload } => Err(
+            Failure::Fatal(ExplorerError::SubscriptionRejected(payload.to_string())),
+        ),
+        ServerMessage::Complete => Err(Failure::Dropped(ExplorerError::SubscriptionClosed)),
+    }
+}
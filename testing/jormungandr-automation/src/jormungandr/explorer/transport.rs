@@ -0,0 +1,170 @@
+use super::client::{GraphQlClient, GraphQlClientError};
+use graphql_client::QueryBody;
+use std::collections::HashMap;
+
+/// Carries a query to an explorer and brings back the body of its response.
+/// [`GraphQlClient`] sends it over HTTP, other transports let an
+/// [`Explorer`](super::Explorer) answer queries without any explorer running.
+pub trait GraphQlTransport: Send + Sync {
+    fn send(&self, query: &QueryBody<serde_json::Value>) -> Result<String, GraphQlClientError>;
+}
+
+impl GraphQlTransport for GraphQlClient {
+    fn send(&self, query: &QueryBody<serde_json::Value>) -> Result<String, GraphQlClientError> {
+        self.run_body(QueryBody {
+            variables: &query.variables,
+            query: query.query,
+            operation_name: query.operation_name,
+        })
+    }
+}
+
+/// Answers each query with the response registered for its operation name,
+/// whatever its variables.
+#[derive(Debug, Clone, Default)]
+pub struct MockTransport {
+    responses: HashMap<String, String>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answers the queries named `operation_name`, e.g. `LastBlock`, with
+    /// `response`, the whole response body including its `data` field.
+    pub fn with_response<S: Into<String>>(
+        &mut self,
+        operation_name: S,
+        response: serde_json::Value,
+    ) -> &mut Self {
+        self.responses
+            .insert(operation_name.into(), response.to_string());
+        self
+    }
+}
+
+impl GraphQlTransport for MockTransport {
+    fn send(&self, query: &QueryBody<serde_json::Value>) -> Result<String, GraphQlClientError> {
+        self.responses
+            .get(query.operation_name)
+            .cloned()
+            .ok_or_else(|| GraphQlClientError::NoMockResponse(query.operation_name.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jormungandr::explorer::{Explorer, ExplorerError};
+    use jormungandr_lib::crypto::hash::Hash;
+    use serde_json::json;
+
+    #[test]
+    pub fn last_block_is_read_from_mock_response() {
+        let mut transport = MockTransport::new();
+        transport.with_response(
+            "LastBlock",
+            json!({ "data": { "tip": { "block": {
+                "id": "b6b3b9e2b2966fb2f7e0ef9bb19a1d5aaee8a7a7e4fa2fd4775978d3f1a871b7",
+                "chainLength": "12",
+                "date": { "epoch": { "id": "3" }, "slot": "7" },
+                "leader": { "__typename": "Pool", "id": "pool" },
+            } } } }),
+        );
+        let mut explorer = Explorer::with_transport(transport);
+        explorer.disable_logs();
+
+        let last_block = explorer.last_block().unwrap();
+        assert_eq!(last_block.chain_length(), 12);
+        assert_eq!(last_block.block_date(), "3.7".parse().unwrap());
+        assert_eq!(last_block.producer().as_deref(), Some("pool"));
+
+        assert!(matches!(
+            explorer.settings(),
+            Err(ExplorerError::ClientError(GraphQlClientError::NoMockResponse(name))) if name == "Settings"
+        ));
+    }
+
+    #[test]
+    pub fn unknown_ids_are_recognised_by_error_code() {
+        let id: Hash = "b6b3b9e2b2966fb2f7e0ef9bb19a1d5aaee8a7a7e4fa2fd4775978d3f1a871b7"
+            .parse()
+            .unwrap();
+        let explorer = |extensions| {
+            let mut transport = MockTransport::new();
+            transport.with_response(
+                "VotePlanById",
+                json!({ "data": null, "errors": [{
+                    "message": "resource not found Vote plan with id not found",
+                    "extensions": extensions,
+                }] }),
+            );
+            let mut explorer = Explorer::with_transport(transport);
+            explorer.disable_logs();
+            explorer
+        };
+
+        let not_found = explorer(json!({ "code": "NOT_FOUND" }));
+        assert!(not_found.vote_plan(id).unwrap().is_none());
+
+        let internal = explorer(json!({ "code": "INTERNAL_ERROR" }));
+        assert!(matches!(
+            internal.vote_plan(id),
+            Err(ExplorerError::GraphQlErrors(_))
+        ));
+    }
+
+    #[test]
+    pub fn missing_fields_are_recognised_by_introspection() {
+        let explorer = |query_fields| {
+            let mut transport = MockTransport::new();
+            transport.with_response(
+                "Version",
+                json!({ "data": null, "errors": [{ "message": "validation failed" }] }),
+            );
+            transport.with_response(
+                "IntrospectionQuery",
+                json!({ "data": { "__schema": {
+                    "queryType": { "name": "Query" },
+                    "types": [{ "name": "Query", "kind": "OBJECT", "fields": query_fields }],
+                } } }),
+            );
+            let mut explorer = Explorer::with_transport(transport);
+            explorer.disable_logs();
+            explorer
+        };
+
+        let older = explorer(json!([{ "name": "tip" }]));
+        assert!(matches!(
+            older.version(),
+            Err(ExplorerError::UnsupportedFeature("version"))
+        ));
+
+        let current = explorer(json!([{ "name": "tip" }, { "name": "version" }]));
+        assert!(matches!(
+            current.version(),
+            Err(ExplorerError::GraphQlErrors(_))
+        ));
+    }
+
+    #[test]
+    pub fn failed_transaction_lookups_are_reported_per_item() {
+        let id: Hash = "b6b3b9e2b2966fb2f7e0ef9bb19a1d5aaee8a7a7e4fa2fd4775978d3f1a871b7"
+            .parse()
+            .unwrap();
+        let mut transport = MockTransport::new();
+        transport.with_response(
+            "TransactionById",
+            json!({ "data": null, "errors": [{ "message": "resource not found" }] }),
+        );
+        let mut explorer = Explorer::with_transport(transport);
+        explorer.disable_logs();
+
+        assert!(explorer.transactions(&[]).unwrap().is_empty());
+
+        let responses = explorer.transactions(&[id, id]).unwrap();
+        assert_eq!(responses.len(), 2);
+        assert!(responses.iter().all(|response| response.errors.is_some()));
+    }
+}
@@ -0,0 +1,151 @@
+use super::{
+    data::{
+        all_vote_plans::{
+            AllVotePlansTipAllVotePlansEdgesNode,
+            AllVotePlansTipAllVotePlansEdgesNodeProposalsTally,
+        },
+        vote_plan_by_id::{VotePlanByIdVotePlanProposals, VotePlanByIdVotePlanProposalsTally},
+        vote_plan_turnout::{
+            VotePlanTurnoutVotePlanProposals, VotePlanTurnoutVotePlanProposalsTally,
+        },
+    },
+    parse_field, ExplorerError,
+};
+use chain_impl_mockchain::block::BlockDate;
+use std::collections::HashSet;
+
+/// Outcome of looking up the tally of a single proposal.
+///
+/// Missing vote plans, missing proposals and proposals without a tally yet
+/// are told apart, so a test polling too early can tell why it got nothing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProposalTally {
+    NoSuchVotePlan,
+    NoSuchProposal,
+    NotTallied,
+    Public {
+        results: Vec<String>,
+    },
+    /// `results` is `None` while the tally is still encrypted.
+    Private {
+        results: Option<Vec<String>>,
+    },
+}
+
+impl From<VotePlanByIdVotePlanProposals> for ProposalTally {
+    fn from(proposal: VotePlanByIdVotePlanProposals) -> Self {
+        match proposal.tally {
+            None => ProposalTally::NotTallied,
+            Some(VotePlanByIdVotePlanProposalsTally::TallyPublicStatus(tally)) => {
+                ProposalTally::Public {
+                    results: tally.results,
+                }
+            }
+            Some(VotePlanByIdVotePlanProposalsTally::TallyPrivateStatus(tally)) => {
+                ProposalTally::Private {
+                    results: tally.results,
+                }
+            }
+        }
+    }
+}
+
+/// Participation in a single proposal of a vote plan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProposalTurnout {
+    pub proposal_id: String,
+    /// Number of distinct addresses which voted on the proposal.
+    pub voters: usize,
+    /// Total voting power cast, summed from the tally results. The explorer
+    /// does not expose the power of individual votes, so this is `None`
+    /// until a tally is available, which for private vote plans means until
+    /// the tally has been decrypted.
+    pub voting_power: Option<u64>,
+}
+
+impl From<VotePlanTurnoutVotePlanProposals> for ProposalTurnout {
+    fn from(proposal: VotePlanTurnoutVotePlanProposals) -> Self {
+        let results = match proposal.tally {
+            Some(VotePlanTurnoutVotePlanProposalsTally::TallyPublicStatus(tally)) => {
+                Some(tally.results)
+            }
+            Some(VotePlanTurnoutVotePlanProposalsTally::TallyPrivateStatus(tally)) => tally.results,
+            None => None,
+        };
+        let voters: HashSet<String> = proposal
+            .votes
+            .edges
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .map(|edge| edge.node.address.id)
+            .collect();
+
+        Self {
+            proposal_id: proposal.proposal_id,
+            voters: voters.len(),
+            voting_power: results.and_then(|results| {
+                results
+                    .iter()
+                    .map(|weight| weight.parse::<u64>().ok())
+                    .sum()
+            }),
+        }
+    }
+}
+
+/// Where a vote plan is in its lifecycle, relative to the tip of the
+/// explorer, as used by
+/// [`Explorer::vote_plans_by_status`](super::Explorer::vote_plans_by_status).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VoteStatus {
+    /// Voting has not started yet.
+    Upcoming,
+    /// Votes can be cast.
+    Active,
+    /// Voting has ended but some proposal has no tally results yet, either
+    /// because no tally was submitted or because it is still encrypted.
+    Ended,
+    /// Voting has ended and every proposal has tally results.
+    Tallied,
+}
+
+impl VoteStatus {
+    pub(super) fn of(
+        vote_plan: &AllVotePlansTipAllVotePlansEdgesNode,
+        tip: BlockDate,
+    ) -> Result<Self, ExplorerError> {
+        let vote_start = BlockDate {
+            epoch: parse_field("voteStart", vote_plan.vote_start.epoch.id.clone())?,
+            slot_id: parse_field("voteStart", vote_plan.vote_start.slot.clone())?,
+        };
+        let vote_end = BlockDate {
+            epoch: parse_field("voteEnd", vote_plan.vote_end.epoch.id.clone())?,
+            slot_id: parse_field("voteEnd", vote_plan.vote_end.slot.clone())?,
+        };
+
+        if tip < vote_start {
+            return Ok(VoteStatus::Upcoming);
+        }
+        if tip < vote_end {
+            return Ok(VoteStatus::Active);
+        }
+        let tallied = vote_plan
+            .proposals
+            .iter()
+            .all(|proposal| match &proposal.tally {
+                Some(AllVotePlansTipAllVotePlansEdgesNodeProposalsTally::TallyPublicStatus(_)) => {
+                    true
+                }
+                Some(AllVotePlansTipAllVotePlansEdgesNodeProposalsTally::TallyPrivateStatus(
+                    tally,
+                )) => tally.results.is_some(),
+                None => false,
+            });
+        Ok(if tallied {
+            VoteStatus::Tallied
+        } else {
+            VoteStatus::Ended
+        })
+    }
+}
@@ -1,6 +1,20 @@
-use crate::jormungandr::explorer::{data::last_block, BlockDate};
-use chain_impl_mockchain::block::BlockDate as LibBlockDate;
+use crate::jormungandr::explorer::{
+    data::{
+        all_blocks, blocks_by_chain_length, epoch, last_block, settings, transactions_by_address,
+        PoolId,
+    },
+    BlockDate, ExplorerError,
+};
+use chain_impl_mockchain::{
+    block::BlockDate as LibBlockDate,
+    chaintypes::ConsensusVersion,
+    fee::{LinearFee, PerCertificateFee, PerVoteCertificateFee},
+};
 use graphql_client::Response;
+use jormungandr_lib::{crypto::hash::Hash, interfaces::EpochStabilityDepth};
+use std::convert::TryFrom;
+use std::num::NonZeroU64;
+use std::str::FromStr;
 
 #[derive(Debug)]
 pub struct LastBlockResponse {
@@ -12,10 +26,22 @@ impl LastBlockResponse {
         Self { data }
     }
 
+    pub fn into_response(self) -> Response<last_block::ResponseData> {
+        self.data
+    }
+
     pub fn block(&self) -> &last_block::LastBlockTipBlock {
         &self.data.data.as_ref().unwrap().tip.block
     }
 
+    pub(super) fn has_data(&self) -> bool {
+        self.data.data.is_some()
+    }
+
+    pub fn chain_length(&self) -> u32 {
+        self.block().chain_length.parse().unwrap()
+    }
+
     pub fn block_date(&self) -> BlockDate {
         let date = &self.data.data.as_ref().unwrap().tip.block.date;
 
@@ -25,4 +51,348 @@ impl LastBlockResponse {
         };
         BlockDate::from(block_date)
     }
+
+    /// Stake pool which produced the tip, `None` for the genesis block and
+    /// for blocks of BFT leaders (see [`is_bft`](Self::is_bft)).
+    pub fn producer(&self) -> Option<PoolId> {
+        match &self.block().leader {
+            Some(last_block::LastBlockTipBlockLeader::Pool(pool)) => Some(pool.id.clone()),
+            _ => None,
+        }
+    }
+
+    /// Whether the tip was produced by a BFT leader rather than a stake pool.
+    pub fn is_bft(&self) -> bool {
+        matches!(
+            self.block().leader,
+            Some(last_block::LastBlockTipBlockLeader::BftLeader)
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct SettingsResponse {
+    data: Response<settings::ResponseData>,
+}
+
+impl SettingsResponse {
+    pub fn new(data: Response<settings::ResponseData>) -> Self {
+        Self { data }
+    }
+
+    pub fn into_response(self) -> Response<settings::ResponseData> {
+        self.data
+    }
+
+    pub fn settings(&self) -> &settings::SettingsSettings {
+        &self.data.data.as_ref().unwrap().settings
+    }
+
+    pub fn fees(&self) -> LinearFee {
+        let fees = &self.settings().fees;
+        let mut linear_fee = LinearFee::new(
+            fees.constant as u64,
+            fees.coefficient as u64,
+            fees.certificate as u64,
+        );
+        let per_certificate_fees = &fees.per_certificate_fees;
+        linear_fee.per_certificate_fees(PerCertificateFee::new(
+            per_certificate_fees
+                .certificate_pool_registration
+                .and_then(NonZeroU64::new),
+            per_certificate_fees
+                .certificate_stake_delegation
+                .and_then(NonZeroU64::new),
+            per_certificate_fees
+                .certificate_owner_stake_delegation
+                .and_then(NonZeroU64::new),
+        ));
+        let per_vote_certificate_fees = &fees.per_vote_certificate_fees;
+        linear_fee.per_vote_certificate_fees(PerVoteCertificateFee::new(
+            per_vote_certificate_fees
+                .certificate_vote_plan
+                .and_then(NonZeroU64::new),
+            per_vote_certificate_fees
+                .certificate_vote_cast
+                .and_then(NonZeroU64::new),
+        ));
+        linear_fee
+    }
+
+    /// Fails with [`ExplorerError::MalformedField`] on a depth which does
+    /// not fit the `u32` of the node settings.
+    pub fn epoch_stability_depth(&self) -> Result<EpochStabilityDepth, ExplorerError> {
+        let depth = self.settings().epoch_stability_depth.epoch_stability_depth;
+        u32::try_from(depth)
+            .map(EpochStabilityDepth::from)
+            .map_err(|_| ExplorerError::MalformedField {
+                field: "epoch stability depth",
+                value: depth.to_string(),
+            })
+    }
+
+    /// Fails with [`ExplorerError::MalformedField`] on a consensus this
+    /// library does not know about.
+    pub fn consensus_version(&self) -> Result<ConsensusVersion, ExplorerError> {
+        match &self.settings().consensus_version.consensus_type {
+            settings::ConsensusTypeEnum::BFT => Ok(ConsensusVersion::Bft),
+            settings::ConsensusTypeEnum::GENESISPRAOS => Ok(ConsensusVersion::GenesisPraos),
+            settings::ConsensusTypeEnum::Other(other) => Err(ExplorerError::MalformedField {
+                field: "consensus version",
+                value: other.clone(),
+            }),
+        }
+    }
+}
+
+/// Where the chain is according to the explorer, as returned by
+/// [`Explorer::tip_summary`](super::Explorer::tip_summary).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TipSummary {
+    pub hash: Hash,
+    pub chain_length: u32,
+    pub block_date: BlockDate,
+    pub epoch_stability_depth: u32,
+}
+
+/// A block as listed by
+/// [`Explorer::iter_all_blocks`](super::Explorer::iter_all_blocks).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockSummary {
+    pub hash: Hash,
+    pub chain_length: u32,
+    pub block_date: BlockDate,
+}
+
+/// Where a transaction is according to the explorer, as returned by
+/// [`Explorer::transaction_status`](super::Explorer::transaction_status).
+/// The explorer only knows about transactions in blocks, so a transaction
+/// still pending in a node mempool and a rejected one are both `NotFound`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxStatus {
+    /// Included in `block`, the first block listing it if it is in more
+    /// than one branch.
+    InBlock {
+        block: Hash,
+        date: BlockDate,
+    },
+    NotFound,
+}
+
+#[derive(Debug)]
+pub struct TransactionsByAddressResponse {
+    data: Response<transactions_by_address::ResponseData>,
+}
+
+impl TransactionsByAddressResponse {
+    pub fn new(data: Response<transactions_by_address::ResponseData>) -> Self {
+        Self { data }
+    }
+
+    pub fn into_response(self) -> Response<transactions_by_address::ResponseData> {
+        self.data
+    }
+
+    /// Transactions touching the address, in the order returned by the
+    /// explorer. An address that never appeared on chain yields an empty
+    /// list rather than an error.
+    pub fn transactions(
+        &self,
+    ) -> Vec<&transactions_by_address::TransactionsByAddressTipTransactionsByAddressEdgesNode> {
+        self.data
+            .data
+            .as_ref()
+            .unwrap()
+            .tip
+            .transactions_by_address
+            .edges
+            .iter()
+            .flatten()
+            .flatten()
+            .map(|edge| &edge.node)
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transactions().is_empty()
+    }
+
+    pub fn has_next_page(&self) -> bool {
+        self.data
+            .data
+            .as_ref()
+            .unwrap()
+            .tip
+            .transactions_by_address
+            .page_info
+            .has_next_page
+    }
+}
+
+#[derive(Debug)]
+pub struct BlocksResponse {
+    data: Response<all_blocks::ResponseData>,
+}
+
+impl BlocksResponse {
+    pub fn new(data: Response<all_blocks::ResponseData>) -> Self {
+        Self { data }
+    }
+
+    pub fn into_response(self) -> Response<all_blocks::ResponseData> {
+        self.data
+    }
+
+    /// Blocks in the order returned by the explorer, oldest first.
+    pub fn blocks(&self) -> Vec<&all_blocks::AllBlocksTipBlocksEdgesNode> {
+        self.data
+            .data
+            .as_ref()
+            .unwrap()
+            .tip
+            .blocks
+            .edges
+            .iter()
+            .flatten()
+            .flatten()
+            .map(|edge| &edge.node)
+            .collect()
+    }
+
+    pub fn hashes(&self) -> Vec<Hash> {
+        self.blocks()
+            .iter()
+            .map(|block| Hash::from_str(&block.id).unwrap())
+            .collect()
+    }
+
+    pub fn block_dates(&self) -> Vec<BlockDate> {
+        self.blocks()
+            .iter()
+            .map(|block| {
+                BlockDate::from(LibBlockDate {
+                    epoch: block.date.epoch.id.parse().unwrap(),
+                    slot_id: block.date.slot.parse().unwrap(),
+                })
+            })
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.blocks().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of blocks on the chain, which may be more than were returned.
+    pub fn total_count(&self) -> i64 {
+        self.data.data.as_ref().unwrap().tip.blocks.total_count
+    }
+}
+
+#[derive(Debug)]
+pub struct BlocksAtChainLengthResponse {
+    data: Response<blocks_by_chain_length::ResponseData>,
+}
+
+impl BlocksAtChainLengthResponse {
+    pub fn new(data: Response<blocks_by_chain_length::ResponseData>) -> Self {
+        Self { data }
+    }
+
+    pub fn into_response(self) -> Response<blocks_by_chain_length::ResponseData> {
+        self.data
+    }
+
+    /// Hashes of every block at the chain length, one per fork.
+    pub fn hashes(&self) -> Vec<Hash> {
+        self.data
+            .data
+            .as_ref()
+            .unwrap()
+            .blocks_by_chain_length
+            .iter()
+            .map(|block| Hash::from_str(&block.id).unwrap())
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.data
+            .data
+            .as_ref()
+            .unwrap()
+            .blocks_by_chain_length
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[derive(Debug)]
+pub struct EpochResponse {
+    data: Response<epoch::ResponseData>,
+}
+
+impl EpochResponse {
+    pub fn new(data: Response<epoch::ResponseData>) -> Self {
+        Self { data }
+    }
+
+    pub fn into_response(self) -> Response<epoch::ResponseData> {
+        self.data
+    }
+
+    pub fn epoch(&self) -> &epoch::EpochEpoch {
+        &self.data.data.as_ref().unwrap().epoch
+    }
+
+    pub fn id(&self) -> u32 {
+        self.epoch().id.parse().unwrap()
+    }
+
+    pub fn first_block(&self) -> Option<Hash> {
+        self.epoch()
+            .first_block
+            .as_ref()
+            .map(|block| Hash::from_str(&block.id).unwrap())
+    }
+
+    pub fn last_block(&self) -> Option<Hash> {
+        self.epoch()
+            .last_block
+            .as_ref()
+            .map(|block| Hash::from_str(&block.id).unwrap())
+    }
+
+    /// Number of blocks in the epoch, which may be more than were returned.
+    pub fn total_blocks(&self) -> i64 {
+        self.epoch().total_blocks
+    }
+
+    /// Hashes of the blocks of the epoch returned by the query, limited by
+    /// the requested number of blocks.
+    pub fn hashes(&self) -> Vec<Hash> {
+        self.data
+            .data
+            .as_ref()
+            .unwrap()
+            .tip
+            .blocks_by_epoch
+            .iter()
+            .flat_map(|connection| connection.edges.iter().flatten().flatten())
+            .map(|edge| Hash::from_str(&edge.node.id).unwrap())
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.hashes().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
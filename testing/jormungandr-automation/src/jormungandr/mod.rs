@@ -1,5 +1,4 @@
 mod configuration;
-mod explorer;
 mod fragment_node;
 mod legacy;
 mod logger;
@@ -9,6 +8,7 @@ mod rest;
 mod starter;
 mod verifier;
 
+pub mod explorer;
 pub mod grpc;
 
 pub use self::{
@@ -16,7 +16,10 @@ pub use self::{
         get_available_port, Block0ConfigurationBuilder, ConfigurationBuilder, JormungandrParams,
         NodeConfigBuilder, SecretModelFactory, TestConfig,
     },
-    explorer::{compare_schema as compare_explorer_schema, Explorer, ExplorerError},
+    explorer::{
+        compare_schema as compare_explorer_schema, into_data as explorer_response_data,
+        AsyncExplorer, Explorer, ExplorerBuilder, ExplorerError,
+    },
     fragment_node::{FragmentNode, FragmentNodeError, MemPoolCheck},
     legacy::{
         download_last_n_releases, get_jormungandr_bin, version_0_8_19, BackwardCompatibleRest,
@@ -296,18 +296,7 @@ impl JormungandrProcess {
     }
 
     pub fn explorer(&self) -> Explorer {
-        let mut p2p_public_address = self.p2p_public_address.clone();
-        let port = match p2p_public_address.pop().unwrap() {
-            multiaddr::Protocol::Tcp(port) => port,
-            _ => todo!("explorer can only be attached through grpc(http)"),
-        };
-
-        let address = match p2p_public_address.pop().unwrap() {
-            multiaddr::Protocol::Ip4(address) => address,
-            _ => todo!("only ipv4 supported for now"),
-        };
-
-        Explorer::new(format!("http://{}:{}/", address, port), self.temp_dir())
+        Explorer::from_node(self, None).expect("explorer did not start")
     }
 
     pub fn to_trusted_peer(&self) -> TrustedPeer {
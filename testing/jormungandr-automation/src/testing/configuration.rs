@@ -24,13 +24,16 @@ pub fn get_explorer_app() -> PathBuf {
     get_app_from_current_dir(JOR_EXPLORER_NAME)
 }
 
+/// Get the path the explorer executable is expected at, without checking
+/// that it exists
+pub fn explorer_app_path() -> PathBuf {
+    const JOR_EXPLORER_NAME: &str = env!("JOR_EXPLORER_NAME");
+    app_path_in_current_dir(JOR_EXPLORER_NAME)
+}
+
 /// Get executable from current environment
 pub fn get_app_from_current_dir(app_name: &str) -> PathBuf {
-    let mut path = get_working_directory();
-    path.push(app_name);
-    if cfg!(windows) {
-        path.set_extension("exe");
-    }
+    let path = app_path_in_current_dir(app_name);
     assert!(
         path.is_file(),
         "File does not exist: {:?}, pwd: {:?}",
@@ -40,6 +43,15 @@ pub fn get_app_from_current_dir(app_name: &str) -> PathBuf {
     path
 }
 
+fn app_path_in_current_dir(app_name: &str) -> PathBuf {
+    let mut path = get_working_directory();
+    path.push(app_name);
+    if cfg!(windows) {
+        path.set_extension("exe");
+    }
+    path
+}
+
 /// Gets working directory
 /// Uses std::env::current_exe() for this purpose.
 /// Current exe directory is ./target/{profile}/deps/{app_name}.exe
@@ -0,0 +1,73 @@
+use crate::jormungandr::{
+    explorer::{parse_field, ExplorerError},
+    Explorer, JormungandrRest, RestError,
+};
+use chain_impl_mockchain::block::BlockDate as LibBlockDate;
+use jormungandr_lib::{
+    crypto::hash::Hash,
+    interfaces::{BlockDate, FragmentStatus},
+};
+use jortestkit::process::Wait;
+use std::time::Duration;
+use thiserror::Error;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Error)]
+pub enum FragmentWaitError {
+    #[error("fragment {fragment_id} was rejected: {reason}")]
+    Rejected { fragment_id: Hash, reason: String },
+    #[error("fragment {fragment_id} did not appear in a block within {timeout:?}")]
+    Timeout {
+        fragment_id: Hash,
+        timeout: Duration,
+    },
+    #[error(transparent)]
+    Explorer(#[from] ExplorerError),
+    #[error(transparent)]
+    Rest(#[from] RestError),
+}
+
+/// Waits up to `timeout` for the transaction of `fragment_id` to be indexed
+/// by the explorer, returning the date of the first block containing it.
+/// The node is asked about the fragment while waiting, so that a fragment it
+/// rejected fails fast with [`FragmentWaitError::Rejected`] rather than
+/// with [`FragmentWaitError::Timeout`].
+pub fn wait_for_fragment_in_block(
+    fragment_id: Hash,
+    explorer: &Explorer,
+    rest: &JormungandrRest,
+    timeout: Duration,
+) -> Result<BlockDate, FragmentWaitError> {
+    let attempts = (timeout.as_millis() / POLL_INTERVAL.as_millis()).max(1);
+    let mut wait = Wait::new(POLL_INTERVAL, attempts as _);
+    loop {
+        // an unknown transaction is answered with errors until it is indexed
+        let response = explorer.transaction(fragment_id)?;
+        let first_block = response
+            .data
+            .and_then(|data| data.transaction.blocks.into_iter().next());
+        if let Some(block) = first_block {
+            return Ok(BlockDate::from(LibBlockDate {
+                epoch: parse_field("epoch", block.date.epoch.id)?,
+                slot_id: parse_field("slot", block.date.slot)?,
+            }));
+        }
+
+        let statuses = rest.fragments_statuses(vec![fragment_id.to_string()])?;
+        if let Some(FragmentStatus::Rejected { reason }) = statuses.get(&fragment_id.to_string()) {
+            return Err(FragmentWaitError::Rejected {
+                fragment_id,
+                reason: reason.clone(),
+            });
+        }
+
+        if wait.timeout_reached() {
+            return Err(FragmentWaitError::Timeout {
+                fragment_id,
+                timeout,
+            });
+        }
+        wait.advance();
+    }
+}
@@ -3,6 +3,7 @@ pub mod benchmark;
 pub mod block0;
 pub mod collector;
 pub mod configuration;
+pub mod fragment;
 pub mod keys;
 pub mod observer;
 pub mod panic;
@@ -28,6 +29,7 @@ pub use benchmark::sync::{
     ensure_node_is_in_sync_with_others, ensure_nodes_are_in_sync, MeasurementReportInterval,
     MeasurementReporter, SyncNode, SyncNodeError, SyncWaitParams,
 };
+pub use fragment::{wait_for_fragment_in_block, FragmentWaitError};
 pub use storage::{BranchCount, StopCriteria, StorageBuilder};
 pub use verify::{assert, assert_equals, Error as VerificationError};
 pub use vit::{VoteCastCounter, VotePlanBuilder, VotePlanExtension};